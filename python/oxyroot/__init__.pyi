@@ -1,42 +1,283 @@
-from typing import Iterator, List, Optional
+from typing import Any, Callable, Dict, Iterator, List, Optional, Tuple, Union, overload
 import numpy as np
+import pandas as pd
+import pyarrow as pa
+
+class OxyrootWarning(UserWarning): ...
 
 class RootFile:
     path: str
     def __init__(self, path: str) -> None: ...
     def keys(self) -> List[str]: ...
+    def keys_with_types(self) -> List[Tuple[str, str]]: ...
+    def trees(self) -> List[str]: ...
+    def get_string(self, name: str) -> str: ...
+    def info(self) -> Dict[str, Union[int, str]]: ...
+    def close(self) -> None: ...
+    def __enter__(self) -> "RootFile": ...
+    def __exit__(self, exc_type: Optional[type], exc_value: Optional[BaseException], traceback: Optional[object]) -> bool: ...
     def __getitem__(self, name: str) -> Tree: ...
+    def __len__(self) -> int: ...
+    def __contains__(self, name: str) -> bool: ...
 
 class Tree:
     path: str
     name: str
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
     def branches(self) -> List[str]: ...
+    def has_branch(self, name: str) -> bool: ...
+    def validate(self) -> List[str]: ...
+    def add_friend(self, path: str, tree_name: str) -> None: ...
+    def typenames(self) -> Dict[str, str]: ...
+    def schema(self) -> Dict[str, str]: ...
+    def describe(self) -> Dict[str, Any]: ...
+    def size_estimate(self, columns: Optional[List[str]] = None) -> int: ...
+    @property
+    def title(self) -> str: ...
+    @property
+    def num_entries(self) -> int: ...
+    def evaluate(self, expression: str, columns_needed: Optional[List[str]] = None, entry_start: Optional[int] = None, entry_stop: Optional[int] = None) -> np.ndarray: ...
+    def arrays(self, columns: Optional[List[str]] = None, entry_start: Optional[int] = None, entry_stop: Optional[int] = None, strict: bool = False, char_as_string: bool = False, cut: Optional[str] = None, aliases: Optional[Dict[str, str]] = None, split_fixed_arrays: bool = False, missing_values: Optional[List[float]] = None, dtypes: Optional[Dict[str, str]] = None, include_unsupported: bool = False, enum_maps: Optional[Dict[str, Dict[int, str]]] = None, drop_all_null: bool = False, step: int = 1, select_types: Optional[List[str]] = None) -> Dict[str, Union[np.ndarray, np.ma.MaskedArray, list]]: ...
     def __getitem__(self, name: str) -> Branch: ...
     def __iter__(self) -> Iterator[Branch]: ...
-    def to_parquet(self, output_file: str, overwrite: bool = False, compression: str = "snappy", columns: Optional[List[str]] = None) -> None: ...
+    def iter_arrays(self, columns: Optional[List[str]] = None, strict: bool = False) -> ArrayIterator: ...
+    def iter_batches(self, batch_size: int, columns: Optional[List[str]] = None, strict: bool = False) -> BatchIterator: ...
+    def take(self, indices: Union[List[int], np.ndarray], columns: Optional[List[str]] = None, strict: bool = False) -> Dict[str, np.ndarray]: ...
+    def to_pandas(self, columns: Optional[List[str]] = None, ignore_columns: Optional[List[str]] = None, entry_start: Optional[int] = None, entry_stop: Optional[int] = None, strict: bool = False) -> pd.DataFrame: ...
+    def to_structured_array(self, columns: Optional[List[str]] = None, entry_start: Optional[int] = None, entry_stop: Optional[int] = None, strict: bool = False) -> np.ndarray: ...
+    def to_arrow(self, columns: Optional[List[str]] = None, entry_start: Optional[int] = None, entry_stop: Optional[int] = None, strict: bool = False, string_as_categorical: bool = False) -> pa.Table: ...
+    def to_parquet(self, output_file: str, overwrite: bool = False, compression: str = "snappy", columns: Optional[List[str]] = None, entry_start: Optional[int] = None, entry_stop: Optional[int] = None, nullable: bool = True, strict: bool = False, row_group_size: int = 1048576, compression_level: Optional[int] = None, cut: Optional[str] = None, aliases: Optional[Dict[str, str]] = None, split_fixed_arrays: bool = False, missing_values: Optional[List[float]] = None, dtypes: Optional[Dict[str, str]] = None, string_as_categorical: bool = False, dictionary_enabled: Optional[bool] = None, data_page_size_limit: Optional[int] = None, write_batch_size: Optional[int] = None, ignore_columns: Optional[List[str]] = None, summary: bool = False) -> Optional[Dict[str, Dict[str, Any]]]: ...
 
 class Branch:
     path: str
     tree_name: str
     name: str
-    def array(self) -> np.ndarray: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+    def array(self, entry_start: Optional[int] = None, entry_stop: Optional[int] = None, char_as_string: bool = False, step: int = 1, missing_values: Optional[List[float]] = None) -> Union[np.ndarray, np.ma.MaskedArray]: ...
+    def array_f32(self, entry_start: Optional[int] = None, entry_stop: Optional[int] = None) -> np.ndarray: ...
+    def array_f64(self, entry_start: Optional[int] = None, entry_stop: Optional[int] = None) -> np.ndarray: ...
+    def array_i8(self, entry_start: Optional[int] = None, entry_stop: Optional[int] = None) -> np.ndarray: ...
+    def array_i16(self, entry_start: Optional[int] = None, entry_stop: Optional[int] = None) -> np.ndarray: ...
+    def array_i32(self, entry_start: Optional[int] = None, entry_stop: Optional[int] = None) -> np.ndarray: ...
+    def array_i64(self, entry_start: Optional[int] = None, entry_stop: Optional[int] = None) -> np.ndarray: ...
+    def array_u8(self, entry_start: Optional[int] = None, entry_stop: Optional[int] = None) -> np.ndarray: ...
+    def array_u16(self, entry_start: Optional[int] = None, entry_stop: Optional[int] = None) -> np.ndarray: ...
+    def array_u32(self, entry_start: Optional[int] = None, entry_stop: Optional[int] = None) -> np.ndarray: ...
+    def array_u64(self, entry_start: Optional[int] = None, entry_stop: Optional[int] = None) -> np.ndarray: ...
+    def array_with_offsets(self, entry_start: Optional[int] = None, entry_stop: Optional[int] = None) -> Tuple[np.ndarray, np.ndarray]: ...
+    def stats(self, entry_start: Optional[int] = None, entry_stop: Optional[int] = None) -> Dict[str, Any]: ...
+    def tolist(self, entry_start: Optional[int] = None, entry_stop: Optional[int] = None, char_as_string: bool = False) -> list: ...
+    def take(self, indices: Union[List[int], np.ndarray], char_as_string: bool = False) -> np.ndarray: ...
+    def raw(self, entry_start: Optional[int] = None, entry_stop: Optional[int] = None) -> List[bytes]: ...
+    def exists(self) -> bool: ...
     @property
     def typename(self) -> str: ...
+    @property
+    def is_array(self) -> bool: ...
+    @property
+    def leaf_count(self) -> Optional[int]: ...
+
+class MultiFile:
+    paths: List[str]
+    def keys(self) -> List[str]: ...
+    def __getitem__(self, name: str) -> Chain: ...
+
+class Chain:
+    name: str
+    def __init__(self, name: str, paths: Optional[List[str]] = None) -> None: ...
+    def add(self, path: str) -> int: ...
+    @property
+    def paths(self) -> List[str]: ...
+    def branches(self) -> List[str]: ...
+    @property
+    def num_entries(self) -> int: ...
+    def failed_paths(self) -> List[str]: ...
+    def arrays(self, columns: Optional[List[str]] = None, strict: bool = False, progress: Optional[Callable[[int, int], None]] = None, require_columns: Optional[List[str]] = None, fill_value: Optional[Any] = None, promote_ints: bool = False, add_source_column: Optional[str] = None) -> Dict[str, Union[np.ndarray, list]]: ...
+    def iter_batches(self, batch_size: int, columns: Optional[List[str]] = None, strict: bool = False, promote_ints: bool = False) -> ChainBatchIterator: ...
 
 class BranchIterator:
     def __iter__(self) -> "BranchIterator": ...
     def __next__(self) -> Optional[Branch]: ...
 
+class ArrayIterator:
+    def __iter__(self) -> "ArrayIterator": ...
+    def __next__(self) -> Tuple[str, np.ndarray]: ...
+
+class ChainBatchIterator:
+    def __iter__(self) -> "ChainBatchIterator": ...
+    def __next__(self) -> Dict[str, np.ndarray]: ...
+
+class BatchIterator:
+    def __iter__(self) -> "BatchIterator": ...
+    def __next__(self) -> Dict[str, np.ndarray]: ...
+
+class ParquetWriter:
+    path: str
+    def __init__(self, output_file: str, overwrite: bool = False, compression: str = "snappy", compression_level: Optional[int] = None, row_group_size: int = 1048576) -> None: ...
+    def write_tree(self, tree: Tree, columns: Optional[List[str]] = None, entry_start: Optional[int] = None, entry_stop: Optional[int] = None, strict: bool = False) -> int: ...
+    def close(self) -> None: ...
+    def __enter__(self) -> "ParquetWriter": ...
+    def __exit__(self, exc_type: Any, exc_value: Any, traceback: Any) -> bool: ...
+
+@overload
+def open(path: str, validate: bool = False) -> Union[RootFile, MultiFile]: ...
+@overload
+def open(path: List[str], validate: bool = False) -> MultiFile: ...
 def open(
-    path: str,
-) -> RootFile:
+    path: Union[str, List[str]],
+    validate: bool = False,
+) -> Union[RootFile, MultiFile]:
     """
-    Opens a ROOT file.
+    Opens a ROOT file, or several files at once.
 
     Args:
-        path: Path of the ROOT file.
+        path: Path or http(s)/root:// URL of the ROOT file, a glob pattern
+            such as "data/*.root", or a list of paths, URLs and patterns.
+            Remote files are downloaded in full to a temporary file on first
+            use, with the GIL released; root:// URLs need `xrdcp` on the PATH.
+        validate: Open every file now instead of on first use. A missing
+            local file raises FileNotFoundError, and a file that can't be
+            read as ROOT raises ValueError.
 
     Returns:
-        A RootFile object.
+        A RootFile for a single path, otherwise a MultiFile whose trees
+        are read as one Chain across all matched files.
+    """
+    ...
+
+def read(path: str, tree_name: str, columns: Optional[List[str]] = None) -> Dict[str, Union[np.ndarray, np.ma.MaskedArray, list]]:
+    """
+    Reads a tree in one call, the same as `open(path)[tree_name].arrays(columns)`.
+
+    Args:
+        path: Path or http(s)/root:// URL of the ROOT file.
+        tree_name: The tree to read.
+        columns: Branch names or glob patterns to read. Defaults to all
+            branches.
+
+    Returns:
+        A dict of NumPy arrays keyed by branch name.
+    """
+    ...
+
+def open_bytes(data: bytes) -> RootFile:
+    """
+    Opens a ROOT file held in memory.
+
+    oxyroot reads from paths only, so the bytes are written to a temporary
+    file. The file is removed once the RootFile and every Tree and Branch
+    taken from it are gone.
+
+    Args:
+        data: The complete contents of a ROOT file.
+
+    Returns:
+        A RootFile, checked straight away; invalid data raises ValueError.
+    """
+    ...
+
+def set_memory_limit(limit: Optional[int]) -> None:
+    """
+    Caps the memory a single read may use.
+
+    Before reading, `arrays()`, `to_pandas()`, `to_structured_array()` and
+    `to_arrow()` compare `Tree.size_estimate()` for the requested columns
+    and entries with the limit, and raise MemoryError if it is exceeded.
+    Variable-length branches are only partly counted, as their sizes are
+    unknown until read.
+
+    Args:
+        limit: The budget in bytes, or None to remove the limit.
+    """
+    ...
+
+def set_num_threads(num_threads: int) -> None:
+    """
+    Sets how many files `files_to_parquet()` converts at once, and how
+    many threads `Tree.to_parquet()` reads branches on.
+
+    Args:
+        num_threads: The number of threads, or 0 (the default) for one per
+            CPU core.
+    """
+    ...
+
+def get_num_threads() -> int:
+    """
+    Returns how many threads `files_to_parquet()` and `Tree.to_parquet()`
+    use: the value given to `set_num_threads()`, or the number of CPU cores
+    if it is 0.
+    """
+    ...
+
+def trees_to_parquet_dataset(
+    paths: Union[str, List[str]],
+    tree_name: str,
+    output_dir: str,
+    partition_cols: Optional[List[str]] = None,
+    compression: str = "zstd",
+    progress: Optional[Callable[[int, int], None]] = None,
+    overwrite: bool = False,
+) -> List[str]:
+    """
+    Writes one tree from several ROOT files as a parquet dataset.
+
+    Args:
+        paths: A path, glob pattern, or list of paths and patterns.
+        tree_name: Name of the tree to read from every file.
+        output_dir: Directory that receives one parquet file per input file.
+        partition_cols: Columns to Hive-partition by, as `column=value`
+            directories, with values percent-encoded as in Arrow. These
+            columns are not stored in the files.
+        compression: Parquet codec, as for `Tree.to_parquet`.
+        progress: Called with `(done, total)` after each input file is written.
+        overwrite: Replace existing part files instead of raising ValueError.
+
+    Returns:
+        The paths of the parquet files written.
+    """
+    ...
+
+def files_to_parquet(
+    paths: Union[str, List[str]],
+    tree_name: str,
+    output_dir: str,
+    compression: str = "zstd",
+    overwrite: bool = False,
+) -> List[str]:
+    """
+    Converts one tree in each of several ROOT files to its own parquet file,
+    several files at a time in parallel.
+
+    Args:
+        paths: A path, glob pattern, or list of paths and patterns.
+        tree_name: Name of the tree to read from every file.
+        output_dir: Directory that receives `<stem>.parquet` per input file.
+        compression: Parquet codec, as for `Tree.to_parquet`.
+        overwrite: Replace parquet files that already exist.
+
+    Returns:
+        The paths of the parquet files written, in input order.
+    """
+    ...
+
+def dataframe_to_root(
+    data: Union[Dict[str, Union[np.ndarray, list]], pd.DataFrame],
+    output_file: str,
+    tree_name: str,
+    overwrite: bool = False,
+) -> None:
+    """
+    Writes columns to a new ROOT file as a single TTree.
+
+    Args:
+        data: A dict of one-dimensional arrays or lists, as returned by
+            `Tree.arrays()`, or a pandas DataFrame. Every column becomes a
+            branch; numeric, bool and string columns are supported.
+        output_file: Path of the ROOT file to create.
+        tree_name: Name of the tree to write.
+        overwrite: Replace `output_file` if it already exists.
     """
     ...
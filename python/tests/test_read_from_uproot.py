@@ -1,7 +1,16 @@
 import oxyroot
 import uproot
 import numpy as np
+import functools
+import http.server
 import os
+import pytest
+import socket
+import subprocess
+import sys
+import threading
+import time
+import warnings
 
 print(oxyroot.__version__)
 
@@ -16,8 +25,1377 @@ def test_read_from_uproot():
         f["tree1"].extend({"branch1": input})
         
 
-    output = oxyroot.open(file_name)["tree1"]["branch1"].array()
+    tree = oxyroot.open(file_name)["tree1"]
+    assert(tree.num_entries == len(input))
+
+    output = tree["branch1"].array()
     assert(type(output) is np.ndarray)
     assert(np.array_equal(input, output))
 
     os.remove(file_name)
+
+def test_read_bool_branch():
+    input = np.array([True, False, True])
+    file_name = "test_bool.root"
+
+    with uproot.recreate(file_name) as f:
+        f.mktree("tree1", {"passed_selection": np.bool_})
+        f["tree1"].extend({"passed_selection": input})
+
+    output = oxyroot.open(file_name)["tree1"]["passed_selection"].array()
+    assert(output.dtype == np.bool_)
+    assert(np.array_equal(input, output))
+
+    os.remove(file_name)
+
+def test_read_small_int_branches():
+    file_name = "test_small_ints.root"
+    inputs = {
+        "i8": np.array([-1, 0, 1], dtype=np.int8),
+        "u8": np.array([0, 1, 255], dtype=np.uint8),
+        "i16": np.array([-300, 0, 300], dtype=np.int16),
+        "u16": np.array([0, 1, 65535], dtype=np.uint16),
+    }
+
+    with uproot.recreate(file_name) as f:
+        f.mktree("tree1", {name: values.dtype for name, values in inputs.items()})
+        f["tree1"].extend(inputs)
+
+    tree = oxyroot.open(file_name)["tree1"]
+    for name, values in inputs.items():
+        output = tree[name].array()
+        assert(output.dtype == values.dtype)
+        assert(np.array_equal(values, output))
+
+    os.remove(file_name)
+
+def test_read_entry_range():
+    input = np.arange(10, dtype=np.float64)
+    file_name = "test_range.root"
+
+    with uproot.recreate(file_name) as f:
+        f.mktree("tree1", {"branch1": np.float64, "branch2": np.int32})
+        f["tree1"].extend({"branch1": input, "branch2": input.astype(np.int32)})
+
+    tree = oxyroot.open(file_name)["tree1"]
+    assert(np.array_equal(tree["branch1"].array(entry_start=2, entry_stop=5), input[2:5]))
+    assert(np.array_equal(tree["branch1"].array(entry_stop=100), input))
+
+    arrays = tree.arrays(entry_start=8)
+    assert(sorted(arrays.keys()) == ["branch1", "branch2"])
+    assert(np.array_equal(arrays["branch2"], input[8:].astype(np.int32)))
+
+    try:
+        tree["branch1"].array(entry_start=5, entry_stop=2)
+        assert(False)
+    except ValueError:
+        pass
+
+    os.remove(file_name)
+
+def test_arrays_select_types():
+    file_name = "test_select_types.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"x": np.zeros(2), "y": np.zeros(2, dtype=np.float32), "n": np.zeros(2, dtype=np.int32)}
+
+    tree = oxyroot.open(file_name)["tree1"]
+    assert(sorted(tree.arrays(select_types=["float", "double"])) == ["x", "y"])
+    assert(list(tree.arrays(select_types=["int32_t"])) == ["n"])
+    assert(list(tree.arrays(columns=["x", "n"], select_types=["double"])) == ["x"])
+
+    os.remove(file_name)
+
+def test_read_with_step():
+    input = np.arange(10, dtype=np.float64)
+    file_name = "test_step.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"branch1": input, "branch2": input.astype(np.int32)}
+
+    tree = oxyroot.open(file_name)["tree1"]
+    assert(np.array_equal(tree["branch1"].array(step=3), input[::3]))
+    assert(np.array_equal(tree["branch1"].array(entry_start=1, entry_stop=8, step=2), input[1:8:2]))
+    arrays = tree.arrays(step=4, cut="branch2 > 0")
+    assert(np.array_equal(arrays["branch1"], [4.0, 8.0]))
+    with pytest.raises(ValueError):
+        tree["branch1"].array(step=0)
+
+    os.remove(file_name)
+
+def test_iter_batches():
+    input = np.arange(10, dtype=np.float64)
+    file_name = "test_batches.root"
+
+    with uproot.recreate(file_name) as f:
+        f.mktree("tree1", {"branch1": np.float64})
+        f["tree1"].extend({"branch1": input})
+
+    batches = list(oxyroot.open(file_name)["tree1"].iter_batches(4))
+    assert([len(batch["branch1"]) for batch in batches] == [4, 4, 2])
+    assert(np.array_equal(np.concatenate([batch["branch1"] for batch in batches]), input))
+
+    os.remove(file_name)
+
+def test_strict_missing_branch():
+    file_name = "test_strict.root"
+
+    with uproot.recreate(file_name) as f:
+        f.mktree("tree1", {"branch1": np.float64})
+        f["tree1"].extend({"branch1": np.array([1.0, 2.0])})
+
+    tree = oxyroot.open(file_name)["tree1"]
+    assert(list(tree.arrays(columns=["branch1", "missing"]).keys()) == ["branch1"])
+    try:
+        tree.arrays(columns=["branch1", "missing"], strict=True)
+        assert(False)
+    except ValueError as e:
+        assert("missing" in str(e))
+
+    os.remove(file_name)
+
+def test_open_multiple_files():
+    file_names = ["test_multi_1.root", "test_multi_2.root"]
+
+    for i, file_name in enumerate(file_names):
+        with uproot.recreate(file_name) as f:
+            f.mktree("tree1", {"branch1": np.float64})
+            f["tree1"].extend({"branch1": np.array([1.0, 2.0]) + i})
+
+    chain = oxyroot.open(file_names)["tree1"]
+    assert(chain.num_entries == 4)
+    assert(np.all(chain.arrays()["branch1"] == np.array([1.0, 2.0, 2.0, 3.0])))
+    assert(oxyroot.open("test_multi_*.root").paths == file_names)
+
+    for file_name in file_names:
+        os.remove(file_name)
+
+def test_to_arrow():
+    pa = pytest.importorskip("pyarrow")
+    file_name = "test_to_arrow.root"
+
+    with uproot.recreate(file_name) as f:
+        f.mktree("tree1", {"branch1": np.float64, "branch2": np.int32})
+        f["tree1"].extend({"branch1": np.array([1.0, 2.0]), "branch2": np.array([3, 4], dtype=np.int32)})
+
+    table = oxyroot.open(file_name)["tree1"].to_arrow()
+    assert(isinstance(table, pa.Table))
+    assert(table.column_names == ["branch1", "branch2"])
+    assert(table["branch2"].to_pylist() == [3, 4])
+
+    os.remove(file_name)
+
+def test_branch_tolist():
+    file_name = "test_tolist.root"
+
+    with uproot.recreate(file_name) as f:
+        f.mktree("tree1", {"branch1": np.uint8})
+        f["tree1"].extend({"branch1": np.array([1, 2, 3], dtype=np.uint8)})
+
+    values = oxyroot.open(file_name)["tree1"]["branch1"].tolist()
+    assert(values == [1, 2, 3])
+    assert(all(type(v) is int for v in values))
+
+    os.remove(file_name)
+
+def test_arrays_cut():
+    file_name = "test_cut.root"
+
+    with uproot.recreate(file_name) as f:
+        f.mktree("tree1", {"pt": np.float64, "eta": np.float64})
+        f["tree1"].extend({"pt": np.array([10.0, 25.0, 30.0, 40.0]), "eta": np.array([0.5, -3.0, 1.0, -2.0])})
+
+    tree = oxyroot.open(file_name)["tree1"]
+    arrays = tree.arrays(columns=["pt"], cut="(pt > 20) & (abs(eta) < 2.4)")
+    assert(list(arrays.keys()) == ["pt"])
+    assert(np.array_equal(arrays["pt"], np.array([30.0, 40.0])))
+    assert(len(tree.arrays(cut="pt > 100")["eta"]) == 0)
+
+    os.remove(file_name)
+
+def test_evaluate():
+    file_name = "test_evaluate.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"px": np.array([3.0, 6.0, 0.0]), "py": np.array([4.0, 8.0, 1.0])}
+
+    tree = oxyroot.open(file_name)["tree1"]
+    assert(np.allclose(tree.evaluate("np.sqrt(px*px + py*py)"), [5.0, 10.0, 1.0]))
+    assert(np.allclose(tree.evaluate("px + py", entry_start=1), [14.0, 1.0]))
+    assert(np.all(tree.evaluate("1.0") == [1.0, 1.0, 1.0]))
+    with pytest.raises(ValueError, match="not found"):
+        tree.evaluate("px", columns_needed=["pz"])
+
+    os.remove(file_name)
+
+def test_arrays_aliases():
+    file_name = "test_aliases.root"
+
+    with uproot.recreate(file_name) as f:
+        f.mktree("tree1", {"b_JetPt_v2": np.float64, "met": np.float64})
+        f["tree1"].extend({"b_JetPt_v2": np.array([1.0, 2.0]), "met": np.array([3.0, 4.0])})
+
+    arrays = oxyroot.open(file_name)["tree1"].arrays(aliases={"b_JetPt_v2": "jet_pt"})
+    assert(sorted(arrays.keys()) == ["jet_pt", "met"])
+    assert(np.array_equal(arrays["jet_pt"], np.array([1.0, 2.0])))
+
+    os.remove(file_name)
+
+def test_fixed_size_arrays():
+    file_name = "test_fixed_arrays.root"
+    pos = np.arange(6, dtype=np.float32).reshape(2, 3)
+
+    with uproot.recreate(file_name) as f:
+        f.mktree("tree1", {"pos": (np.float32, (3,))})
+        f["tree1"].extend({"pos": pos})
+
+    tree = oxyroot.open(file_name)["tree1"]
+    assert(tree["pos"].tolist() == pos.tolist())
+    arrays = tree.arrays(split_fixed_arrays=True)
+    assert(list(arrays.keys()) == ["pos_0", "pos_1", "pos_2"])
+    assert(np.array_equal(arrays["pos_1"], pos[:, 1]))
+
+    os.remove(file_name)
+
+def test_char_as_string():
+    file_name = "test_char_as_string.root"
+    names = np.zeros((2, 8), dtype=np.int8)
+    names[0, :3] = list(b"abc")
+    names[1, :5] = list(b"hello")
+
+    with uproot.recreate(file_name) as f:
+        f.mktree("tree1", {"name": (np.int8, (8,))})
+        f["tree1"].extend({"name": names})
+
+    tree = oxyroot.open(file_name)["tree1"]
+    assert(tree["name"].tolist(char_as_string=True) == ["abc", "hello"])
+    assert(tree.arrays(char_as_string=True)["name"] == ["abc", "hello"])
+    assert(tree["name"].tolist() == names.tolist())
+
+    os.remove(file_name)
+
+SHORT_CHAR_ARRAY_FILE = os.path.join(os.path.dirname(__file__), "data", "short_char_array.root")
+
+def test_short_fixed_array_entries():
+    # Written by oxyroot with a char[8] branch "name", whose title was then
+    # patched to name[9]/B, so every entry is a byte short.
+    tree = oxyroot.open(SHORT_CHAR_ARRAY_FILE)["t"]
+    with pytest.raises(ValueError, match="branch 'name'"):
+        tree["name"].tolist(char_as_string=True)
+    with pytest.raises(ValueError, match="ends early"):
+        tree.arrays()
+
+def test_dataframe_to_root():
+    file_name = "test_write.root"
+    data = {"pt": np.array([1.5, 2.5]), "n": np.array([3, 4], dtype=np.int32), "name": ["a", "bc"]}
+
+    oxyroot.dataframe_to_root(data, file_name, "tree1")
+
+    tree = uproot.open(file_name)["tree1"]
+    assert(np.array_equal(tree["pt"].array(library="np"), data["pt"]))
+    assert(np.array_equal(tree["n"].array(library="np"), data["n"]))
+    assert(list(tree["name"].array(library="np")) == data["name"])
+
+    os.remove(file_name)
+
+def test_arrays_missing_values():
+    file_name = "test_missing.root"
+
+    with uproot.recreate(file_name) as f:
+        f.mktree("tree1", {"pt": np.float32})
+        f["tree1"].extend({"pt": np.array([1.0, -999.0, 3.0], dtype=np.float32)})
+
+    pt = oxyroot.open(file_name)["tree1"].arrays(missing_values=[-999])["pt"]
+    assert(isinstance(pt, np.ma.MaskedArray))
+    assert(list(pt.mask) == [False, True, False])
+    assert(pt.sum() == 4.0)
+
+    os.remove(file_name)
+
+def test_repr():
+    file_name = "test_repr.root"
+
+    with uproot.recreate(file_name) as f:
+        f.mktree("tree1", {"branch1": np.float64})
+        f["tree1"].extend({"branch1": np.array([1.0, 2.0])})
+
+    file = oxyroot.open(file_name)
+    assert(repr(file) == "<RootFile 'test_repr.root'>")
+    assert(repr(file["tree1"]) == "<Tree 'tree1' in 'test_repr.root': 1 branches, 2 entries>")
+    assert(str(file["tree1"]["branch1"]) == "<Branch 'branch1' (double) of tree 'tree1' in 'test_repr.root'>")
+
+    os.remove(file_name)
+
+def test_remote_repr(tmp_path):
+    with uproot.recreate(str(tmp_path / "remote.root")) as f:
+        f["tree1"] = {"branch1": np.array([1.0, 2.0])}
+
+    # The server runs in its own process, as a stand-in for a real one.
+    with socket.socket() as s:
+        s.bind(("127.0.0.1", 0))
+        port = s.getsockname()[1]
+    server = subprocess.Popen(
+        [sys.executable, "-m", "http.server", str(port), "--bind", "127.0.0.1", "--directory", str(tmp_path)],
+        stdout=subprocess.DEVNULL,
+        stderr=subprocess.DEVNULL,
+    )
+    try:
+        for _ in range(100):
+            try:
+                socket.create_connection(("127.0.0.1", port)).close()
+                break
+            except OSError:
+                time.sleep(0.05)
+        url = f"http://127.0.0.1:{port}/remote.root"
+        tree = oxyroot.open(url)["tree1"]
+        assert(repr(tree) == f"<Tree 'tree1' in '{url}'>")
+        assert(repr(tree["branch1"]) == f"<Branch 'branch1' of tree 'tree1' in '{url}'>")
+        assert(tree.num_entries == 2)
+        assert(repr(tree) == f"<Tree 'tree1' in '{url}': 1 branches, 2 entries>")
+    finally:
+        server.terminate()
+        server.wait()
+
+def test_remote_download_releases_gil(tmp_path):
+    with uproot.recreate(str(tmp_path / "remote.root")) as f:
+        f["tree1"] = {"branch1": np.array([1.0, 2.0])}
+
+    # A server thread in this process can only answer if the download lets
+    # go of the GIL.
+    handler = functools.partial(http.server.SimpleHTTPRequestHandler, directory=str(tmp_path))
+    server = http.server.ThreadingHTTPServer(("127.0.0.1", 0), handler)
+    threading.Thread(target=server.serve_forever, daemon=True).start()
+    try:
+        url = f"http://127.0.0.1:{server.server_address[1]}/remote.root"
+        assert(oxyroot.open(url)["tree1"]["branch1"].tolist() == [1.0, 2.0])
+        paths = oxyroot.files_to_parquet(url, "tree1", str(tmp_path))
+        assert(paths == [str(tmp_path / "remote.parquet")])
+    finally:
+        server.shutdown()
+        server.server_close()
+
+def test_tree_title():
+    file_name = "test_title.root"
+
+    with uproot.recreate(file_name) as f:
+        f.mktree("tree1", {"branch1": np.float64}, title="Muon candidates")
+        f["tree1"].extend({"branch1": np.array([1.0])})
+
+    assert(oxyroot.open(file_name)["tree1"].title == "Muon candidates")
+
+    os.remove(file_name)
+
+def test_tree_typenames():
+    file_name = "test_typenames.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"x": np.array([1.0, 2.0]), "n": np.array([1, 2], dtype=np.int32)}
+
+    assert(oxyroot.open(file_name)["tree1"].typenames() == {"x": "double", "n": "int32_t"})
+
+    os.remove(file_name)
+
+def test_arrays_dtypes():
+    file_name = "test_dtypes.root"
+
+    with uproot.recreate(file_name) as f:
+        f.mktree("tree1", {"n": np.int32})
+        f["tree1"].extend({"n": np.array([1, 2, 300], dtype=np.int32)})
+
+    tree = oxyroot.open(file_name)["tree1"]
+    assert(tree.arrays(dtypes={"n": "float64"})["n"].dtype == np.float64)
+    assert(np.array_equal(tree.arrays(dtypes={"n": "int16"})["n"], [1, 2, 300]))
+    with pytest.raises(ValueError, match="'n'"):
+        tree.arrays(dtypes={"n": "complex"})
+    with pytest.raises(ValueError, match="out of range"):
+        tree.arrays(dtypes={"n": "int8"})
+
+    os.remove(file_name)
+
+def test_take():
+    file_name = "test_take.root"
+    input = np.arange(10, dtype=np.float64)
+
+    with uproot.recreate(file_name) as f:
+        f.mktree("tree1", {"branch1": np.float64})
+        f["tree1"].extend({"branch1": input})
+
+    tree = oxyroot.open(file_name)["tree1"]
+    indices = np.array([7, 2, 7, -1])
+    assert(np.array_equal(tree["branch1"].take(indices), input[indices]))
+    assert(np.array_equal(tree.take([3, 1])["branch1"], np.array([3.0, 1.0])))
+    with pytest.raises(ValueError):
+        tree["branch1"].take([10])
+
+    os.remove(file_name)
+
+def test_iter_arrays():
+    file_name = "test_iter_arrays.root"
+
+    with uproot.recreate(file_name) as f:
+        f.mktree("tree1", {"branch1": np.float64, "branch2": np.int32})
+        f["tree1"].extend({"branch1": np.array([1.0, 2.0]), "branch2": np.array([3, 4], dtype=np.int32)})
+
+    pairs = list(oxyroot.open(file_name)["tree1"].iter_arrays())
+    assert([name for name, _ in pairs] == ["branch1", "branch2"])
+    assert(np.array_equal(pairs[1][1], np.array([3, 4], dtype=np.int32)))
+
+    os.remove(file_name)
+
+def test_chain_iter_batches():
+    file_names = ["test_chain_batches_1.root", "test_chain_batches_2.root"]
+
+    for i, file_name in enumerate(file_names):
+        with uproot.recreate(file_name) as f:
+            f.mktree("tree1", {"branch1": np.float64})
+            f["tree1"].extend({"branch1": np.arange(3, dtype=np.float64) + 3 * i})
+
+    batches = list(oxyroot.open(file_names)["tree1"].iter_batches(2))
+    assert([len(batch["branch1"]) for batch in batches] == [2, 1, 2, 1])
+    assert(np.array_equal(np.concatenate([batch["branch1"] for batch in batches]), np.arange(6)))
+
+    for file_name in file_names:
+        os.remove(file_name)
+
+def test_chain_arrays_keep_path_order():
+    file_names = ["test_chain_order_1.root", "test_chain_order_2.root", "test_chain_order_3.root"]
+
+    for i, file_name in enumerate(file_names):
+        with uproot.recreate(file_name) as f:
+            if i == 1:
+                f["other"] = {"branch1": np.array([0.0])}
+            else:
+                f["tree1"] = {"branch1": np.arange(2, dtype=np.float64) + 2 * i}
+
+    arrays = oxyroot.open(file_names[::-1])["tree1"].arrays()
+    assert(np.array_equal(arrays["branch1"], [4.0, 5.0, 0.0, 1.0]))
+
+    for file_name in file_names:
+        os.remove(file_name)
+
+def test_chain_promote_ints():
+    file_names = ["test_promote_1.root", "test_promote_2.root"]
+
+    for file_name, dtype in zip(file_names, [np.int32, np.int16]):
+        with uproot.recreate(file_name) as f:
+            f["tree1"] = {"n": np.array([1, 2], dtype=dtype), "u": np.array([3, 4], dtype=np.uint8)}
+
+    chain = oxyroot.open(file_names)["tree1"]
+    arrays = chain.arrays(promote_ints=True)
+    assert(arrays["n"].dtype == np.int64)
+    assert(arrays["u"].dtype == np.uint64)
+    assert(np.array_equal(arrays["n"], [1, 2, 1, 2]))
+    assert(chain.arrays()["u"].dtype == np.uint8)
+    batches = list(chain.iter_batches(2, promote_ints=True))
+    assert(all(batch["n"].dtype == np.int64 for batch in batches))
+
+    for file_name in file_names:
+        os.remove(file_name)
+
+def test_chain_add_source_column():
+    file_names = ["test_source_1.root", "test_source_2.root"]
+
+    for i, file_name in enumerate(file_names):
+        with uproot.recreate(file_name) as f:
+            f["tree1"] = {"x": np.arange(i + 1, dtype=np.float64)}
+
+    chain = oxyroot.open(file_names)["tree1"]
+    arrays = chain.arrays(add_source_column="source")
+    assert(arrays["source"] == [file_names[0], file_names[1], file_names[1]])
+    assert(len(arrays["x"]) == 3)
+    with pytest.raises(ValueError):
+        chain.arrays(add_source_column="x")
+
+    for file_name in file_names:
+        os.remove(file_name)
+
+def test_chain_add():
+    file_names = ["test_chain_add_1.root", "test_chain_add_2.root"]
+
+    for i, file_name in enumerate(file_names):
+        with uproot.recreate(file_name) as f:
+            f["tree1"] = {"branch1": np.arange(2, dtype=np.float64) + 2 * i}
+
+    chain = oxyroot.Chain("tree1")
+    assert(chain.add(file_names[0]) == 1)
+    assert(chain.num_entries == 2)
+    assert(chain.add("test_chain_add_*.root") == 2)
+    assert(chain.num_entries == 6)
+    assert(np.array_equal(oxyroot.Chain("tree1", file_names).arrays()["branch1"], np.arange(4)))
+    with pytest.raises(ValueError, match="No files match"):
+        chain.add("test_chain_missing_*.root")
+
+    for file_name in file_names:
+        os.remove(file_name)
+
+def test_chain_arrays_no_readable_file():
+    file_name = "test_chain_no_tree.root"
+
+    with uproot.recreate(file_name) as f:
+        f.mktree("other", {"branch1": np.float64})
+        f["other"].extend({"branch1": np.array([1.0])})
+
+    with pytest.raises(ValueError, match="No file contains a readable tree 'tree1'"):
+        oxyroot.open([file_name])["tree1"].arrays()
+
+    os.remove(file_name)
+
+def test_chain_failed_paths():
+    file_names = ["test_failed_1.root", "test_failed_2.root"]
+
+    with uproot.recreate(file_names[0]) as f:
+        f["tree1"] = {"branch1": np.array([1.0, 2.0])}
+    with uproot.recreate(file_names[1]) as f:
+        f["other"] = {"branch1": np.array([3.0])}
+
+    chain = oxyroot.open(file_names)["tree1"]
+    assert(chain.failed_paths() == [file_names[1]])
+    with pytest.warns(oxyroot.OxyrootWarning, match="test_failed_2.root"):
+        assert(np.array_equal(chain.arrays()["branch1"], [1.0, 2.0]))
+    with pytest.raises(ValueError, match="test_failed_2.root"):
+        chain.arrays(strict=True)
+
+    for file_name in file_names:
+        os.remove(file_name)
+
+def test_chain_progress():
+    file_names = ["test_progress_1.root", "test_progress_2.root"]
+
+    for file_name in file_names:
+        with uproot.recreate(file_name) as f:
+            f["tree1"] = {"branch1": np.array([1.0, 2.0])}
+
+    calls = []
+    oxyroot.open(file_names)["tree1"].arrays(progress=lambda done, total: calls.append((done, total)))
+    assert(calls == [(1, 2), (2, 2)])
+
+    for file_name in file_names:
+        os.remove(file_name)
+
+def test_branch_leaf_count():
+    file_name = "test_leaf_count.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"scalar": np.array([1.0, 2.0]), "fixed": np.zeros((2, 3), dtype=np.float32)}
+
+    tree = oxyroot.open(file_name)["tree1"]
+    assert(not tree["scalar"].is_array)
+    assert(tree["scalar"].leaf_count == 1)
+    assert(tree["fixed"].is_array)
+    assert(tree["fixed"].leaf_count == 3)
+
+    os.remove(file_name)
+
+def test_branch_raw():
+    file_name = "test_branch_raw.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"x": np.array([1.5, 2.5, 3.5]), "pos": np.arange(9, dtype=np.float32).reshape(3, 3)}
+
+    tree = oxyroot.open(file_name)["tree1"]
+    assert(tree["x"].raw() == [np.float64(v).astype(">f8").tobytes() for v in [1.5, 2.5, 3.5]])
+    assert(tree["x"].raw(entry_start=1, entry_stop=2) == [np.float64(2.5).astype(">f8").tobytes()])
+    assert(tree["pos"].raw()[1] == np.array([3, 4, 5], dtype=">f4").tobytes())
+
+    os.remove(file_name)
+
+def test_branch_typed_arrays():
+    file_name = "test_typed_arrays.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"x": np.array([1.5, 2.5], dtype=np.float32), "n": np.array([1, 2], dtype=np.int64)}
+
+    tree = oxyroot.open(file_name)["tree1"]
+    assert(np.all(tree["x"].array_f32() == [1.5, 2.5]))
+    assert(np.all(tree["n"].array_i64(entry_start=1) == [2]))
+    with pytest.raises(ValueError, match="not float64"):
+        tree["x"].array_f64()
+    with pytest.raises(ValueError, match="not int32"):
+        tree["n"].array_i32()
+
+    os.remove(file_name)
+
+def test_branch_array_with_offsets():
+    file_name = "test_array_with_offsets.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"pos": np.arange(6, dtype=np.float32).reshape(3, 2), "x": np.zeros(3)}
+
+    tree = oxyroot.open(file_name)["tree1"]
+    values, offsets = tree["pos"].array_with_offsets()
+    assert(np.all(values == np.arange(6)))
+    assert(np.all(offsets == [0, 2, 4, 6]))
+    assert(offsets.dtype == np.int64)
+    values, offsets = tree["pos"].array_with_offsets(entry_start=2)
+    assert(np.all(values == [4, 5]))
+    assert(np.all(offsets == [0, 2]))
+    with pytest.raises(ValueError, match="not a numeric array"):
+        tree["x"].array_with_offsets()
+
+    os.remove(file_name)
+
+def test_branch_stats():
+    file_name = "test_branch_stats.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"x": np.array([1.0, np.nan, 3.0, 8.0]), "n": np.array([2, -1, 5, 0], dtype=np.int32)}
+
+    tree = oxyroot.open(file_name)["tree1"]
+    assert(tree["x"].stats() == {"min": 1.0, "max": 8.0, "mean": 4.0, "count": 3, "null_count": 1})
+    assert(tree["n"].stats(entry_stop=2) == {"min": -1.0, "max": 2.0, "mean": 0.5, "count": 2, "null_count": 0})
+    assert(tree["n"].stats(entry_start=4)["mean"] is None)
+
+    os.remove(file_name)
+
+def test_tree_and_branch_hashable():
+    file_name = "test_hashable.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"x": np.zeros(2), "y": np.zeros(2)}
+
+    first = oxyroot.open(file_name)["tree1"]
+    second = oxyroot.open(file_name)["tree1"]
+    assert(first == second)
+    assert(len({first, second}) == 1)
+    cache = {first["x"]: 1}
+    assert(cache[second["x"]] == 1)
+    assert(first["x"] != first["y"])
+
+    os.remove(file_name)
+
+def test_char_star_branch():
+    file_name = "test_char_star.root"
+
+    oxyroot.dataframe_to_root({"name": ["a", "bc"]}, file_name, "tree1")
+
+    tree = oxyroot.open(file_name)["tree1"]
+    assert(tree["name"].typename == "char*")
+    assert(list(tree["name"].array()) == ["a", "bc"])
+    assert(tree.schema() == {"name": "str"})
+
+    os.remove(file_name)
+
+def test_arrays_include_unsupported():
+    file_name = "test_include_unsupported.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"n": np.array([1, 2, 3], dtype=np.int32), "flags": np.zeros((3, 2), dtype=bool)}
+
+    tree = oxyroot.open(file_name)["tree1"]
+    assert("flags" not in tree.arrays())
+    arrays = tree.arrays(include_unsupported=True, cut="n > 1")
+    assert(list(arrays["n"]) == [2, 3])
+    assert(arrays["flags"] == [None, None])
+
+    os.remove(file_name)
+
+def test_arrays_drop_all_null():
+    file_name = "test_drop_all_null.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"x": np.array([1.0, -999.0]), "y": np.array([-999.0, -999.0]), "flags": np.zeros((2, 2), dtype=bool)}
+
+    tree = oxyroot.open(file_name)["tree1"]
+    arrays = tree.arrays(missing_values=[-999], include_unsupported=True, drop_all_null=True)
+    assert(list(arrays) == ["x"])
+    assert("y" in tree.arrays(missing_values=[-999], drop_all_null=True, entry_stop=0))
+    assert("y" in tree.arrays(missing_values=[-999]))
+
+    os.remove(file_name)
+
+def test_skipped_branch_warning():
+    file_name = "test_skipped_warning.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"n": np.array([1, 2], dtype=np.int32), "flags": np.zeros((2, 2), dtype=bool)}
+
+    tree = oxyroot.open(file_name)["tree1"]
+    with pytest.warns(oxyroot.OxyrootWarning, match="branch 'flags'"):
+        arrays = tree.arrays()
+    assert(list(arrays) == ["n"])
+    assert(issubclass(oxyroot.OxyrootWarning, UserWarning))
+    with warnings.catch_warnings():
+        warnings.simplefilter("error", oxyroot.OxyrootWarning)
+        with pytest.raises(oxyroot.OxyrootWarning):
+            tree.arrays()
+
+    os.remove(file_name)
+
+def test_files_to_parquet(tmp_path):
+    pq = pytest.importorskip("pyarrow.parquet")
+    file_names = [str(tmp_path / "run1.root"), str(tmp_path / "run2.root")]
+
+    for i, file_name in enumerate(file_names):
+        with uproot.recreate(file_name) as f:
+            f["tree1"] = {
+                "branch1": np.array([1.0, 2.0, 3.0]) + i,
+                "n": np.arange(3, dtype=np.int32) * (i + 1),
+            }
+
+    output_dir = tmp_path / "parquet"
+    written = oxyroot.files_to_parquet(file_names, "tree1", str(output_dir))
+    assert(written == [str(output_dir / "run1.parquet"), str(output_dir / "run2.parquet")])
+    for file_name, output_file in zip(file_names, written):
+        arrays = oxyroot.open(file_name)["tree1"].arrays()
+        table = pq.read_table(output_file)
+        assert(table.column_names == list(arrays))
+        assert(table.num_rows == 3)
+        for name, array in arrays.items():
+            assert(table.column(name).to_pylist() == array.tolist())
+
+    with pytest.raises(ValueError, match="overwrite=True"):
+        oxyroot.files_to_parquet(file_names, "tree1", str(output_dir))
+
+def test_files_to_parquet_skipped_branch_warning(tmp_path):
+    file_names = [str(tmp_path / f"run{i}.root") for i in range(4)]
+    for file_name in file_names:
+        with uproot.recreate(file_name) as f:
+            f["tree1"] = {"n": np.array([1, 2], dtype=np.int32), "flags": np.zeros((2, 2), dtype=bool)}
+
+    # Workers don't take the GIL, so each skip is warned about from the
+    # calling thread once the files are written.
+    try:
+        oxyroot.set_num_threads(2)
+        with pytest.warns(oxyroot.OxyrootWarning, match="run3.root") as record:
+            written = oxyroot.files_to_parquet(file_names, "tree1", str(tmp_path / "parquet"))
+        assert(len(written) == 4)
+        assert(len([w for w in record if "branch 'flags'" in str(w.message)]) == 4)
+    finally:
+        oxyroot.set_num_threads(0)
+
+def test_trees_to_parquet_dataset(tmp_path):
+    ds = pytest.importorskip("pyarrow.dataset")
+    file_names = [str(tmp_path / "run1.root"), str(tmp_path / "run2.root")]
+
+    with uproot.recreate(file_names[0]) as f:
+        f["tree1"] = {"run": np.array([1, 1, 2], dtype=np.int32), "x": np.array([1.0, 2.0, 3.0])}
+    with uproot.recreate(file_names[1]) as f:
+        f["tree1"] = {"run": np.array([2, 2], dtype=np.int32), "x": np.array([4.0, 5.0])}
+
+    output_dir = tmp_path / "dataset"
+    written = oxyroot.trees_to_parquet_dataset(file_names, "tree1", str(output_dir), partition_cols=["run"])
+    assert(sorted(os.listdir(output_dir)) == ["run=1", "run=2"])
+    assert(len(written) == 3)
+
+    table = ds.dataset(str(output_dir), partitioning="hive").to_table()
+    runs = table.column("run").to_pylist()
+    assert(table.num_rows == 5)
+    assert(runs.count(1) == 2 and runs.count(2) == 3)
+
+    with pytest.raises(ValueError, match="overwrite=True"):
+        oxyroot.trees_to_parquet_dataset(file_names, "tree1", str(output_dir), partition_cols=["run"])
+    assert(len(oxyroot.trees_to_parquet_dataset(file_names, "tree1", str(output_dir), partition_cols=["run"], overwrite=True)) == 3)
+
+def test_trees_to_parquet_dataset_escapes_values(tmp_path):
+    ds = pytest.importorskip("pyarrow.dataset")
+    # Written by oxyroot: uproot can't write string branches. The labels
+    # are "a/b", "..", "c d" and "a/b".
+    file_name = os.path.join(os.path.dirname(__file__), "data", "partition_labels.root")
+
+    output_dir = tmp_path / "dataset"
+    oxyroot.trees_to_parquet_dataset(file_name, "tree1", str(output_dir), partition_cols=["label"])
+    assert(sorted(os.listdir(output_dir)) == ["label=..", "label=a%2Fb", "label=c%20d"])
+
+    table = ds.dataset(str(output_dir), partitioning="hive").to_table()
+    labels = table.column("label").to_pylist()
+    assert(sorted(labels) == ["..", "a/b", "a/b", "c d"])
+
+def test_set_num_threads(tmp_path):
+    file_names = [str(tmp_path / f"run{i}.root") for i in range(3)]
+    for file_name in file_names:
+        with uproot.recreate(file_name) as f:
+            f["tree1"] = {"branch1": np.array([1.0, 2.0])}
+
+    try:
+        oxyroot.set_num_threads(1)
+        assert(oxyroot.get_num_threads() == 1)
+        assert(len(oxyroot.files_to_parquet(file_names, "tree1", str(tmp_path / "one"))) == 3)
+        oxyroot.set_num_threads(0)
+        assert(oxyroot.get_num_threads() >= 1)
+        assert(len(oxyroot.files_to_parquet(file_names, "tree1", str(tmp_path / "all"))) == 3)
+        with pytest.raises(OverflowError):
+            oxyroot.set_num_threads(-1)
+    finally:
+        oxyroot.set_num_threads(0)
+
+def test_string_as_categorical():
+    pa = pytest.importorskip("pyarrow")
+    file_name = "test_categorical.root"
+
+    oxyroot.dataframe_to_root({"trigger": ["mu", "e", "mu"]}, file_name, "tree1")
+
+    table = oxyroot.open(file_name)["tree1"].to_arrow(string_as_categorical=True)
+    assert(pa.types.is_dictionary(table["trigger"].type))
+    assert(table["trigger"].to_pylist() == ["mu", "e", "mu"])
+
+    os.remove(file_name)
+
+def test_size_estimate():
+    file_name = "test_size_estimate.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"x": np.zeros(10), "n": np.zeros(10, dtype=np.int32), "pos": np.zeros((10, 3), dtype=np.float32)}
+
+    tree = oxyroot.open(file_name)["tree1"]
+    assert(tree.size_estimate(["x"]) == 80)
+    assert(tree.size_estimate() == 80 + 40 + 120)
+
+    os.remove(file_name)
+
+def test_describe():
+    file_name = "test_describe.root"
+
+    with uproot.recreate(file_name, compression=uproot.ZLIB(4)) as f:
+        f["tree1"] = {"x": np.zeros(5), "pos": np.zeros((5, 3), dtype=np.float32)}
+
+    layout = oxyroot.open(file_name)["tree1"].describe()
+    assert(layout["entries"] == 5)
+    assert(layout["compression_algorithm"] == "zlib")
+    assert(layout["branches"]["x"]["dtype"] == "float64")
+    assert(layout["branches"]["x"]["leaf_count"] == 1)
+    assert(layout["branches"]["pos"]["typename"] == "float[3]")
+    assert(layout["branches"]["pos"]["leaf_count"] == 3)
+    assert(layout["branches"]["pos"]["branches"] == {})
+
+    os.remove(file_name)
+
+def test_validate():
+    file_name = "test_validate_tree.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"x": np.zeros(3), "n": np.zeros(3, dtype=np.int32)}
+
+    assert(oxyroot.open(file_name)["tree1"].validate() == [])
+
+    os.remove(file_name)
+
+def test_set_memory_limit():
+    file_name = "test_memory_limit.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"x": np.zeros(10), "n": np.zeros(10, dtype=np.int32)}
+
+    tree = oxyroot.open(file_name)["tree1"]
+    oxyroot.set_memory_limit(100)
+    try:
+        with pytest.raises(MemoryError, match="120 bytes"):
+            tree.arrays()
+        assert(len(tree.arrays(["x"])["x"]) == 10)
+        assert(len(tree.arrays(entry_stop=5)["n"]) == 5)
+        # take() reads every entry between its first and last index.
+        with pytest.raises(MemoryError, match="120 bytes"):
+            tree.take([0, 9])
+        assert(len(tree.take([0, 1])["x"]) == 2)
+        with pytest.raises(MemoryError, match="120 bytes"):
+            tree.iter_batches(10)
+        assert(sum(len(batch["x"]) for batch in tree.iter_batches(5)) == 10)
+        with pytest.raises(MemoryError, match="240 bytes"):
+            oxyroot.open([file_name, file_name])["tree1"].arrays()
+        assert(len(oxyroot.open([file_name, file_name])["tree1"].arrays(["n"])["n"]) == 20)
+        oxyroot.set_memory_limit(50)
+        arrays = tree.iter_arrays(["n", "x"])
+        assert(next(arrays)[0] == "n")
+        with pytest.raises(MemoryError, match="80 bytes"):
+            next(arrays)
+    finally:
+        oxyroot.set_memory_limit(None)
+    assert(len(tree.arrays()) == 2)
+
+    os.remove(file_name)
+
+def test_open_validate():
+    file_name = "test_validate.root"
+
+    with pytest.raises(FileNotFoundError):
+        oxyroot.open("does_not_exist.root", validate=True)
+
+    with open(file_name, "w") as f:
+        f.write("not a ROOT file")
+    with pytest.raises(ValueError, match="Cannot open"):
+        oxyroot.open(file_name, validate=True)
+    assert(oxyroot.open(file_name).path == file_name)
+
+    os.remove(file_name)
+
+def test_open_bytes():
+    file_name = "test_open_bytes.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"branch1": np.array([1.0, 2.0])}
+    with open(file_name, "rb") as f:
+        data = f.read()
+    os.remove(file_name)
+
+    tree = oxyroot.open_bytes(data)["tree1"]
+    assert(np.array_equal(tree["branch1"].array(), np.array([1.0, 2.0])))
+
+    with pytest.raises(ValueError):
+        oxyroot.open_bytes(b"not a ROOT file")
+
+def test_has_branch():
+    file_name = "test_has_branch.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"branch1": np.array([1.0, 2.0])}
+
+    tree = oxyroot.open(file_name)["tree1"]
+    assert(tree.has_branch("branch1"))
+    assert(not tree.has_branch("missing"))
+    assert(tree["branch1"].exists())
+    assert(not tree["missing"].exists())
+
+    os.remove(file_name)
+
+def test_to_structured_array():
+    file_name = "test_structured.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"x": np.array([1.0, 2.0]), "n": np.array([3, 4], dtype=np.int32), "pos": np.zeros((2, 3), dtype=np.float32)}
+
+    records = oxyroot.open(file_name)["tree1"].to_structured_array()
+    assert(records.dtype.names == ("x", "n", "pos"))
+    assert(records.dtype["n"] == np.int32)
+    assert(list(records["x"]) == [1.0, 2.0])
+    assert(len(records["pos"][0]) == 3)
+
+    os.remove(file_name)
+
+def test_to_parquet_writer_options():
+    pq = pytest.importorskip("pyarrow.parquet")
+    file_name = "test_writer_options.root"
+    output_file = "test_writer_options.parquet"
+
+    oxyroot.dataframe_to_root({"trigger": ["mu", "e", "mu"]}, file_name, "tree1")
+    tree = oxyroot.open(file_name)["tree1"]
+
+    tree.to_parquet(output_file, overwrite=True)
+    encodings = pq.ParquetFile(output_file).metadata.row_group(0).column(0).encodings
+    assert(any("DICTIONARY" in encoding for encoding in encodings))
+
+    tree.to_parquet(output_file, overwrite=True, dictionary_enabled=False, data_page_size_limit=1024, write_batch_size=2)
+    encodings = pq.ParquetFile(output_file).metadata.row_group(0).column(0).encodings
+    assert(not any("DICTIONARY" in encoding for encoding in encodings))
+
+    with pytest.raises(ValueError):
+        tree.to_parquet(output_file, overwrite=True, write_batch_size=0)
+
+    os.remove(file_name)
+    os.remove(output_file)
+
+def test_to_parquet_nullable(tmp_path):
+    pq = pytest.importorskip("pyarrow.parquet")
+    file_name = str(tmp_path / "nullable.root")
+    output_file = str(tmp_path / "nullable.parquet")
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"branch1": np.array([1.0, 2.0])}
+
+    tree = oxyroot.open(file_name)["tree1"]
+    tree.to_parquet(output_file)
+    assert(pq.read_schema(output_file).field("branch1").nullable)
+    tree.to_parquet(output_file, overwrite=True, nullable=False)
+    assert(not pq.read_schema(output_file).field("branch1").nullable)
+
+def test_to_parquet_threads():
+    pq = pytest.importorskip("pyarrow.parquet")
+    file_name = "test_parquet_threads.root"
+
+    # Trees under 100,000 entries are always converted on one thread.
+    entries = 100_000
+    with uproot.recreate(file_name) as f:
+        branches = {f"b{i}": np.arange(entries, dtype=np.float64) * i for i in range(12)}
+        branches["pos"] = np.arange(3 * entries, dtype=np.float32).reshape(entries, 3)
+        f["tree1"] = branches
+
+    tree = oxyroot.open(file_name)["tree1"]
+    tables = []
+    contents = []
+    try:
+        for threads in [1, 4]:
+            oxyroot.set_num_threads(threads)
+            output_file = f"test_parquet_threads_{threads}.parquet"
+            tree.to_parquet(output_file, overwrite=True, row_group_size=30_000, cut="b1 > 10", split_fixed_arrays=True)
+            tables.append(pq.read_table(output_file))
+            with open(output_file, "rb") as f:
+                contents.append(f.read())
+            os.remove(output_file)
+    finally:
+        oxyroot.set_num_threads(0)
+    assert(tables[0].column_names == [f"b{i}" for i in range(12)] + ["pos_0", "pos_1", "pos_2"])
+    assert(tables[0].equals(tables[1]))
+    assert(tables[1].num_rows == entries - 11)
+    assert(contents[0] == contents[1])
+
+    os.remove(file_name)
+
+def test_to_parquet_summary():
+    file_name = "test_parquet_summary.root"
+    output_file = "test_parquet_summary.parquet"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"zeros": np.zeros(1000), "noise": np.random.default_rng(1).random(1000)}
+
+    tree = oxyroot.open(file_name)["tree1"]
+    assert(tree.to_parquet(output_file) is None)
+    summary = tree.to_parquet(output_file, overwrite=True, compression="zstd", summary=True)
+    assert(sorted(summary) == ["noise", "zeros"])
+    assert(summary["zeros"]["codec"] == "zstd")
+    assert(summary["zeros"]["ratio"] > summary["noise"]["ratio"])
+    assert(summary["noise"]["compressed_bytes"] > 0)
+
+    os.remove(file_name)
+    os.remove(output_file)
+
+def test_to_parquet_compression_level(tmp_path):
+    pq = pytest.importorskip("pyarrow.parquet")
+    file_name = str(tmp_path / "compression_level.root")
+    output_file = str(tmp_path / "compression_level.parquet")
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"x": np.arange(100, dtype=np.float64)}
+
+    tree = oxyroot.open(file_name)["tree1"]
+    tree.to_parquet(output_file, compression="zstd", compression_level=19)
+    assert(pq.ParquetFile(output_file).metadata.row_group(0).column(0).compression == "ZSTD")
+    assert(pq.read_table(output_file).column("x").to_pylist() == list(range(100)))
+
+    with pytest.raises(ValueError, match="not supported for snappy"):
+        tree.to_parquet(output_file, overwrite=True, compression="snappy", compression_level=3)
+    with pytest.raises(ValueError, match="Invalid compression level -1 for gzip"):
+        tree.to_parquet(output_file, overwrite=True, compression="gzip", compression_level=-1)
+    with pytest.raises(ValueError, match="0..=10"):
+        tree.to_parquet(output_file, overwrite=True, compression="gzip", compression_level=11)
+
+def test_to_parquet_dtypes_aliases_missing_values(tmp_path):
+    pq = pytest.importorskip("pyarrow.parquet")
+    pa = pytest.importorskip("pyarrow")
+    file_name = str(tmp_path / "transforms.root")
+    output_file = str(tmp_path / "transforms.parquet")
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {
+            "pt": np.array([1.5, -999.0, 3.5]),
+            "n": np.array([1, 2, -999], dtype=np.int32),
+        }
+
+    oxyroot.open(file_name)["tree1"].to_parquet(
+        output_file,
+        dtypes={"pt": "float32", "n": "int64"},
+        aliases={"pt": "muon_pt"},
+        missing_values=[-999],
+    )
+    table = pq.read_table(output_file)
+    assert(table.column_names == ["muon_pt", "n"])
+    assert(table.schema.field("muon_pt").type == pa.float32())
+    assert(table.schema.field("n").type == pa.int64())
+    assert(table.column("muon_pt").to_pylist() == [1.5, None, 3.5])
+    assert(table.column("n").to_pylist() == [1, 2, None])
+    assert(table.column("n").null_count == 1)
+
+def test_to_parquet_ignore_columns():
+    pq = pytest.importorskip("pyarrow.parquet")
+    file_name = "test_parquet_ignore.root"
+    output_file = "test_parquet_ignore.parquet"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"x": np.zeros(2), "raw_a": np.zeros(2), "raw_b": np.zeros(2), "y": np.ones(2)}
+
+    tree = oxyroot.open(file_name)["tree1"]
+    tree.to_parquet(output_file, ignore_columns=["raw_*"])
+    assert(pq.read_table(output_file).column_names == ["x", "y"])
+    tree.to_parquet(output_file, overwrite=True, columns=["x", "raw_a"], ignore_columns=["raw_a"])
+    assert(pq.read_table(output_file).column_names == ["x"])
+
+    os.remove(file_name)
+    os.remove(output_file)
+
+def test_parquet_writer_several_trees():
+    pq = pytest.importorskip("pyarrow.parquet")
+    file_name = "test_parquet_writer.root"
+    output_file = "test_parquet_writer.parquet"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"x": np.arange(3, dtype=np.float64)}
+        f["tree2"] = {"x": np.arange(3, 5, dtype=np.float64)}
+        f["tree3"] = {"y": np.zeros(2, dtype=np.float64)}
+
+    file = oxyroot.open(file_name)
+    with oxyroot.ParquetWriter(output_file, overwrite=True) as writer:
+        assert(writer.write_tree(file["tree1"]) == 3)
+        assert(writer.write_tree(file["tree2"]) == 2)
+        with pytest.raises(ValueError):
+            writer.write_tree(file["tree3"])
+    assert(pq.read_table(output_file).column("x").to_pylist() == [0.0, 1.0, 2.0, 3.0, 4.0])
+    with pytest.raises(ValueError):
+        writer.write_tree(file["tree1"])
+
+    os.remove(file_name)
+    os.remove(output_file)
+
+def test_file_info():
+    file_name = "test_info.root"
+
+    with uproot.recreate(file_name, compression=uproot.ZLIB(4)) as f:
+        f["tree1"] = {"branch1": np.array([1.0, 2.0])}
+
+    info = oxyroot.open(file_name).info()
+    assert(info["compression_algorithm"] == "zlib")
+    assert(info["compression_level"] == 4)
+    assert(info["size"] == os.path.getsize(file_name))
+    assert(info["keys"] == 1)
+    assert(info["version"] > 60000)
+
+    os.remove(file_name)
+
+def test_file_trees():
+    file_name = "test_trees.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"branch1": np.array([1.0, 2.0])}
+        f["hist"] = np.histogram(np.array([1.0, 2.0]), bins=2)
+        f["tree2"] = {"branch2": np.array([3, 4])}
+
+    file = oxyroot.open(file_name)
+    assert("hist" in file.keys())
+    assert(file.trees() == ["tree1", "tree2"])
+    assert(len(file) == len(file.keys()))
+    assert("tree2" in file)
+    assert("missing" not in file)
+
+    os.remove(file_name)
+
+def test_nested_directory_tree():
+    file_name = "test_nested_directory.root"
+
+    with uproot.recreate(file_name) as f:
+        f["Analysis/Events"] = {"branch1": np.array([1.0, 2.0])}
+
+    assert("Analysis" in oxyroot.open(file_name).keys())
+    with pytest.raises(ValueError, match="trees inside directories are not supported yet"):
+        oxyroot.open(file_name)["Analysis/Events"].arrays()
+
+    os.remove(file_name)
+
+def test_file_keys_with_types():
+    file_name = "test_keys_with_types.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"branch1": np.array([1.0, 2.0])}
+        f["hist"] = np.histogram(np.array([1.0, 2.0]), bins=2)
+
+    assert(oxyroot.open(file_name).keys_with_types() == [("tree1", "TTree"), ("hist", "TH1D")])
+
+    os.remove(file_name)
+
+def test_open_context_manager():
+    file_name = "test_context_manager.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"branch1": np.array([1.0, 2.0])}
+
+    with oxyroot.open(file_name) as file:
+        tree = file["tree1"]
+        assert(tree.num_entries == 2)
+
+    # Closing drops the parsed trees, so handles taken from the file reopen
+    # it and see a rewritten file.
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"branch1": np.array([1.0, 2.0, 3.0])}
+    assert(tree.num_entries == 3)
+
+    file = oxyroot.open(file_name)
+    branch = file["tree1"]["branch1"]
+    assert(len(branch.array()) == 3)
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"branch1": np.array([4.0])}
+    file.close()
+    assert(np.array_equal(branch.array(), [4.0]))
+
+    os.remove(file_name)
+
+def test_file_get_string():
+    file_name = "test_get_string.root"
+    config = '{"run": 1, "cuts": "' + "x" * 1000 + '"}'
+
+    with uproot.recreate(file_name) as f:
+        f["config"] = config
+        f["tree1"] = {"branch1": np.array([1.0, 2.0])}
+
+    file = oxyroot.open(file_name)
+    assert(file.get_string("config") == config)
+    with pytest.raises(ValueError, match="not a TObjString"):
+        file.get_string("tree1")
+
+    os.remove(file_name)
+
+def test_empty_tree():
+    file_name = "test_empty_tree.root"
+
+    oxyroot.dataframe_to_root({"x": np.array([], dtype=np.float64), "n": np.array([], dtype=np.int32)}, file_name, "tree1")
+
+    tree = oxyroot.open(file_name)["tree1"]
+    assert(tree.num_entries == 0)
+    arrays = tree.arrays()
+    assert(list(arrays) == ["x", "n"])
+    assert(arrays["x"].dtype == np.float64 and len(arrays["x"]) == 0)
+    assert(arrays["n"].dtype == np.int32 and len(arrays["n"]) == 0)
+    assert(len(tree.arrays(cut="x > 0")["n"]) == 0)
+
+    pd = pytest.importorskip("pandas")
+    df = tree.to_pandas()
+    assert(list(df.columns) == ["x", "n"] and len(df) == 0)
+    assert(df["n"].dtype == np.int32)
+
+    os.remove(file_name)
+
+def test_arrays_enum_maps():
+    file_name = "test_enum_maps.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"channel": np.array([0, 1, 2, 1], dtype=np.int32), "x": np.arange(4.0)}
+
+    tree = oxyroot.open(file_name)["tree1"]
+    arrays = tree.arrays(enum_maps={"channel": {0: "ee", 1: "mumu"}})
+    assert(arrays["channel"] == ["ee", "mumu", None, "mumu"])
+    with pytest.raises(ValueError, match="integer"):
+        tree.arrays(enum_maps={"x": {0: "zero"}})
+
+    os.remove(file_name)
+
+def test_add_friend():
+    file_name = "test_friend_main.root"
+    friend_name = "test_friend.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"x": np.array([1.0, 2.0, 3.0])}
+    with uproot.recreate(friend_name) as f:
+        f["calib"] = {"w": np.array([0.5, 1.5, 2.5]), "x": np.zeros(3)}
+        f["short"] = {"w": np.array([0.5])}
+
+    tree = oxyroot.open(file_name)["tree1"]
+    tree.add_friend(friend_name, "calib")
+    arrays = tree.arrays(cut="w > 1")
+    assert(list(arrays) == ["x", "w"])
+    assert(list(arrays["x"]) == [2.0, 3.0])
+    assert(list(arrays["w"]) == [1.5, 2.5])
+
+    with pytest.raises(ValueError, match="entries"):
+        tree.add_friend(friend_name, "short")
+
+    os.remove(file_name)
+    os.remove(friend_name)
+
+def test_chain_fill_and_require_columns():
+    file_names = ["test_fill_1.root", "test_fill_2.root"]
+
+    with uproot.recreate(file_names[0]) as f:
+        f["tree1"] = {"x": np.array([1.0, 2.0]), "w": np.array([0.5, 0.5])}
+    with uproot.recreate(file_names[1]) as f:
+        f["tree1"] = {"x": np.array([3.0])}
+
+    chain = oxyroot.open(file_names)["tree1"]
+    assert("w" not in chain.arrays())
+    w = chain.arrays(fill_value=np.nan)["w"]
+    assert(w.dtype == np.float64 and len(w) == 3 and np.isnan(w[2]))
+    with pytest.raises(ValueError, match="test_fill_2.root"):
+        chain.arrays(require_columns=["w"])
+
+    # A branch only the second file has is filled in the first.
+    reversed_chain = oxyroot.open(file_names[::-1])["tree1"]
+    assert("w" not in reversed_chain.arrays())
+    arrays = reversed_chain.arrays(fill_value=np.nan)
+    assert(list(arrays) == ["x", "w"])
+    assert(np.isnan(arrays["w"][0]) and list(arrays["w"][1:]) == [0.5, 0.5])
+
+    for file_name in file_names:
+        os.remove(file_name)
+
+def test_read_function():
+    file_name = "test_read_function.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"x": np.array([1.0, 2.0]), "n": np.array([1, 2], dtype=np.int32)}
+
+    arrays = oxyroot.read(file_name, "tree1", columns=["x"])
+    assert(list(arrays) == ["x"])
+    assert(np.array_equal(arrays["x"], [1.0, 2.0]))
+    assert(list(oxyroot.read(file_name, "tree1")) == ["x", "n"])
+
+    os.remove(file_name)
+
+def test_branch_array_missing_values():
+    file_name = "test_branch_missing.root"
+
+    with uproot.recreate(file_name) as f:
+        f["tree1"] = {"n": np.array([4, -999, 6], dtype=np.int32)}
+
+    n = oxyroot.open(file_name)["tree1"]["n"].array(missing_values=[-999])
+    assert(isinstance(n, np.ma.MaskedArray))
+    assert(n.dtype == np.int32)
+    assert(list(n.mask) == [False, True, False])
+    assert(n.sum() == 10)
+
+    os.remove(file_name)
+
+LEAF_LIST_FILE = os.path.join(os.path.dirname(__file__), "data", "leaf_list.root")
+
+def test_leaf_list_arrays():
+    # uproot can't write leaf lists: branch "ab" of this file was written as
+    # int32[3] and its title patched to the leaf list "x:y:nn/I".
+    tree = oxyroot.open(LEAF_LIST_FILE)["t"]
+    arrays = tree.arrays()
+    assert(list(arrays) == ["ab.x", "ab.y", "ab.nn", "k"])
+    assert(arrays["ab.x"].dtype == np.float32 and arrays["ab.nn"].dtype == np.int32)
+    assert(list(arrays["ab.x"]) == [0.5, 1.5, 2.5, 3.5, 4.5])
+    assert(list(arrays["ab.y"]) == [0.0, 2.0, 4.0, 6.0, 8.0])
+    assert(list(arrays["ab.nn"]) == [0, 10, 20, 30, 40])
+    assert(list(tree.arrays(columns=["ab"], include_unsupported=True)) == ["ab.x", "ab.y", "ab.nn"])
+
+    taken = tree.take([3, 1], columns=["ab"])
+    assert(list(taken["ab.nn"]) == [30, 10])
+
+    assert(tree.schema()["ab.nn"] == "int32")
+    assert(tree.size_estimate(columns=["ab"]) == 5 * 12)
+    assert([name for name, _ in tree.iter_arrays()] == ["ab.x", "ab.y", "ab.nn", "k"])
+
+def test_leaf_list_chain_arrays():
+    arrays = oxyroot.open([LEAF_LIST_FILE, LEAF_LIST_FILE])["t"].arrays()
+    assert(list(arrays) == ["ab.x", "ab.y", "ab.nn", "k"])
+    assert(arrays["ab.nn"].dtype == np.int32)
+    assert(list(arrays["ab.nn"]) == [0, 10, 20, 30, 40] * 2)
+    assert(list(arrays["ab.x"]) == [0.5, 1.5, 2.5, 3.5, 4.5] * 2)
+
+def test_leaf_list_to_parquet(tmp_path):
+    pq = pytest.importorskip("pyarrow.parquet")
+    output_file = str(tmp_path / "leaf_list.parquet")
+
+    oxyroot.open(LEAF_LIST_FILE)["t"].to_parquet(output_file)
+    table = pq.read_table(output_file)
+    assert(table.column_names == ["ab.x", "ab.y", "ab.nn", "k"])
+    assert(table.column("ab.nn").to_pylist() == [0, 10, 20, 30, 40])
+
+VECTOR_BOOL_FILE = os.path.join(os.path.dirname(__file__), "data", "vector_bool.root")
+
+def test_vector_bool():
+    # uproot writes jagged bools as bool[n], not std::vector<bool>: this file
+    # was written with oxyroot's Rust writer, taught to write bool elements.
+    tree = oxyroot.open(VECTOR_BOOL_FILE)["tree1"]
+    assert(tree.typenames()["flags"] == "vector<bool>")
+    assert(tree.schema()["flags"] == "list[bool]")
+    flags = tree["flags"].array()
+    assert(all(entry.dtype == np.bool_ for entry in flags))
+    assert([list(entry) for entry in flags] == [[True, False, True], [], [False, True]])
+
+LONG64_FILE = os.path.join(os.path.dirname(__file__), "data", "long64_vectors.root")
+
+def test_long64_vectors():
+    # Only ROOT itself spells 64-bit integers Long64_t: this file was written
+    # with oxyroot's Rust writer, patched to name its vectors that way.
+    tree = oxyroot.open(LONG64_FILE)["t"]
+    assert(tree.typenames() == {"l": "vector<Long64_t>", "ul": "vector<ULong64_t>"})
+    assert(tree.schema() == {"l": "list[int64]", "ul": "list[uint64]"})
+    arrays = tree.arrays()
+    assert(all(entry.dtype == np.int64 for entry in arrays["l"]))
+    assert(all(entry.dtype == np.uint64 for entry in arrays["ul"]))
+    assert(tree["l"].tolist() == [[-(1 << 40), 1], [], [2**63 - 1]])
+    assert(tree["ul"].tolist() == [[2**64 - 1], [1 << 40, 2], []])
@@ -1,35 +1,365 @@
-use ::oxyroot::{Named, RootFile};
-use numpy::IntoPyArray;
-use pyo3::{exceptions::PyValueError, prelude::*, IntoPyObjectExt};
+use ::oxyroot::rbase::TObjString;
+use ::oxyroot::{Branch, Named, RBuffer, ReaderTree, RootFile, Unmarshaler, WriterTree};
+use numpy::{Element, IntoPyArray, PyReadonlyArray1};
+use pyo3::{
+    create_exception,
+    exceptions::{PyFileNotFoundError, PyMemoryError, PyUserWarning, PyValueError},
+    prelude::*,
+    types::{PyBytes, PyDict, PyList, PyString},
+    IntoPyObjectExt,
+};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::ffi::CString;
 use std::fs::File;
-use std::path::Path;
-use std::sync::Arc;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+use tempfile::TempPath;
 
+use arrow::array::AsArray;
 use arrow::array::{
-    ArrayRef, Float32Array, Float64Array, Int32Array, Int64Array, StringArray, UInt32Array,
-    UInt64Array,
+    ArrayRef, BooleanArray, BooleanBuilder, Float32Array, Float64Array, Int16Array, Int32Array,
+    Int64Array, Int8Array, ListArray, ListBuilder, StringArray, UInt16Array, UInt32Array,
+    UInt64Array, UInt8Array,
+};
+use arrow::compute::{
+    cast, cast_with_options, filter_record_batch, nullif, take_record_batch, CastOptions,
 };
-use arrow::datatypes::{DataType, Field, Schema};
-use arrow::record_batch::RecordBatch;
+use arrow::datatypes::{
+    ArrowPrimitiveType, DataType, Field, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type,
+    Int8Type, Schema, SchemaRef, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use arrow::ffi_stream::FFI_ArrowArrayStream;
+use arrow::record_batch::{RecordBatch, RecordBatchIterator};
+use arrow::util::display::array_value_to_string;
 use parquet::arrow::ArrowWriter;
 use parquet::basic::{BrotliLevel, Compression, GzipLevel, ZstdLevel};
 use parquet::file::properties::WriterProperties;
 
-#[pyclass(name = "RootFile")]
+fn is_remote(path: &str) -> bool {
+    ["http://", "https://", "root://"]
+        .iter()
+        .any(|scheme| path.starts_with(scheme))
+}
+
+/// Downloads `url` to a temporary file, over HTTP or, for `root://` URLs, by
+/// running `xrdcp`.
+fn download(url: &str) -> PyResult<TempPath> {
+    let mut file = tempfile::Builder::new().suffix(".root").tempfile()?;
+    if url.starts_with("root://") {
+        let output = Command::new("xrdcp")
+            .args(["-f", "-s", url])
+            .arg(file.path())
+            .output()
+            .map_err(|e| {
+                PyValueError::new_err(format!("Cannot run xrdcp to fetch '{}': {}", url, e))
+            })?;
+        if !output.status.success() {
+            return Err(PyValueError::new_err(format!(
+                "Cannot download '{}': {}",
+                url,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+    } else {
+        let response = ureq::get(url)
+            .call()
+            .map_err(|e| PyValueError::new_err(format!("Cannot download '{}': {}", url, e)))?;
+        std::io::copy(&mut response.into_reader(), file.as_file_mut())?;
+    }
+    Ok(file.into_temp_path())
+}
+
+create_exception!(
+    oxyroot,
+    OxyrootWarning,
+    PyUserWarning,
+    "Issued when a branch or file is skipped because it can't be read."
+);
+
+/// Entries per row group written by `to_parquet`, matching parquet's default.
+const DEFAULT_ROW_GROUP_SIZE: usize = 1024 * 1024;
+
+/// Below these, `to_parquet()` reads on one thread: each extra thread reopens
+/// the file and parses the tree again, which small reads don't make up for.
+const PARALLEL_MIN_ENTRIES: usize = 100_000;
+const PARALLEL_MIN_BRANCHES_PER_THREAD: usize = 4;
+
+/// Bytes a read may allocate, or 0 for no limit; set with `set_memory_limit()`.
+static MEMORY_LIMIT: AtomicUsize = AtomicUsize::new(0);
+
+/// Threads `files_to_parquet()` and `to_parquet()` convert on, or 0 for one
+/// per core; set with `set_num_threads()`.
+static NUM_THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// A ROOT file opened on first use and shared by the `RootFile`, `Tree` and
+/// `Branch` objects created from it, so the file and its trees are parsed once.
+struct FileHandle {
+    path: String,
+    /// Local copy of a remote `path`, or the temporary file `path` points to
+    /// for `open_bytes()`, deleted when the handle is dropped.
+    local: RefCell<Option<TempPath>>,
+    file: RefCell<Option<RootFile>>,
+    trees: RefCell<HashMap<String, Rc<ReaderTree>>>,
+    leaf_lists: RefCell<HashMap<String, Rc<LeafLists>>>,
+    /// Whether a download releases the GIL, which the thread using the
+    /// handle holds unless it is a worker thread.
+    detach_downloads: bool,
+}
+
+impl FileHandle {
+    fn new(path: String) -> Self {
+        FileHandle {
+            path,
+            local: RefCell::new(None),
+            file: RefCell::new(None),
+            trees: RefCell::new(HashMap::new()),
+            leaf_lists: RefCell::new(HashMap::new()),
+            detach_downloads: true,
+        }
+    }
+
+    /// A handle for a worker thread, which must not wait for the GIL.
+    fn for_worker(path: String) -> Self {
+        FileHandle {
+            detach_downloads: false,
+            ..FileHandle::new(path)
+        }
+    }
+
+    /// Writes `data` to a temporary file that lives as long as the handle.
+    /// oxyroot only reads from paths, so in-memory files take this detour.
+    fn from_bytes(data: &[u8]) -> PyResult<Self> {
+        let mut file = tempfile::Builder::new().suffix(".root").tempfile()?;
+        std::io::Write::write_all(file.as_file_mut(), data)?;
+        let local = file.into_temp_path();
+        let handle = FileHandle::new(local.to_string_lossy().into_owned());
+        *handle.local.borrow_mut() = Some(local);
+        Ok(handle)
+    }
+
+    fn with_file<T>(&self, f: impl FnOnce(&mut RootFile) -> T) -> PyResult<T> {
+        let mut file = self.file.borrow_mut();
+        let file = match &mut *file {
+            Some(file) => file,
+            None => file.insert(
+                RootFile::open(self.local_path()?)
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?,
+            ),
+        };
+        Ok(f(file))
+    }
+
+    /// Opens the file now rather than on first use, raising
+    /// `FileNotFoundError` for a missing local path and `ValueError` for a
+    /// file that isn't ROOT.
+    fn validate(&self, py: Python) -> PyResult<()> {
+        if !is_remote(&self.path) && !Path::new(&self.path).exists() {
+            return Err(PyFileNotFoundError::new_err(format!(
+                "No such file: '{}'",
+                self.path
+            )));
+        }
+        self.with_file(|_| ()).map_err(|e| {
+            PyValueError::new_err(format!("Cannot open '{}': {}", self.path, e.value(py)))
+        })
+    }
+
+    /// Whether reading won't trigger a download: the file is local or its
+    /// remote copy was already fetched.
+    fn is_fetched(&self) -> bool {
+        !is_remote(&self.path) || self.local.borrow().is_some()
+    }
+
+    /// The path oxyroot reads from. oxyroot only reads local files, so a remote
+    /// file is downloaded in full on first use, letting other Python threads
+    /// run meanwhile.
+    fn local_path(&self) -> PyResult<PathBuf> {
+        if !is_remote(&self.path) {
+            return Ok(PathBuf::from(&self.path));
+        }
+        let mut local = self.local.borrow_mut();
+        if local.is_none() {
+            let path = self.path.as_str();
+            *local = Some(match self.detach_downloads {
+                true => Python::attach(|py| py.detach(|| download(path)))?,
+                false => download(path)?,
+            });
+        }
+        Ok(local.as_ref().unwrap().to_path_buf())
+    }
+
+    fn tree(&self, name: &str) -> PyResult<Rc<ReaderTree>> {
+        if let Some(tree) = self.trees.borrow().get(name) {
+            return Ok(tree.clone());
+        }
+        // oxyroot only reads keys of the top-level directory, so a path into a
+        // subdirectory can't be resolved yet; say so instead of "key not found".
+        if let Some((dir, _)) = name.trim_start_matches('/').split_once('/') {
+            let is_dir = self.with_file(|file| {
+                file.keys()
+                    .iter()
+                    .any(|k| k.name() == dir && k.class_name().starts_with("TDirectory"))
+            })?;
+            if is_dir {
+                return Err(PyValueError::new_err(format!(
+                    "Cannot read '{}': trees inside directories are not supported yet",
+                    name
+                )));
+            }
+        }
+        let tree = self
+            .with_file(|file| file.get_tree(name))?
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let tree = Rc::new(tree);
+        self.trees
+            .borrow_mut()
+            .insert(name.to_string(), tree.clone());
+        Ok(tree)
+    }
+
+    /// The leaves of each leaf-list branch of tree `name`, by branch name.
+    /// oxyroot doesn't expose leaves, so they are parsed from the titles of
+    /// the tree's plain `TBranch`es, as streamed in its key. A title only
+    /// lists several leaves for a leaf-list branch, whatever type oxyroot
+    /// makes of it. A key that can't be read back, e.g. compressed with a
+    /// codec `root_decompress()` doesn't know, gives no leaf lists, and the
+    /// tree reads as oxyroot sees it.
+    fn leaf_lists(&self, name: &str) -> PyResult<Rc<LeafLists>> {
+        if let Some(leaf_lists) = self.leaf_lists.borrow().get(name) {
+            return Ok(leaf_lists.clone());
+        }
+        let tree = self.tree(name)?;
+        let candidates = tree
+            .branches()
+            .filter(|branch| matches!(branch, Branch::Base(_)))
+            .map(|branch| branch.name())
+            .collect::<HashSet<_>>();
+        let leaf_lists = match candidates.is_empty() {
+            true => HashMap::new(),
+            false => self.key_data(name, "TTree").map_or_else(
+                |_| HashMap::new(),
+                |(data, _)| find_leaf_lists(&data, &candidates),
+            ),
+        };
+        let leaf_lists = Rc::new(leaf_lists);
+        self.leaf_lists
+            .borrow_mut()
+            .insert(name.to_string(), leaf_lists.clone());
+        Ok(leaf_lists)
+    }
+
+    /// The streamed object under key `name`, which must be of `class`, and
+    /// the key's length, which offsets positions within the object.
+    fn key_data(&self, name: &str, class: &str) -> PyResult<(Vec<u8>, u32)> {
+        let key = self
+            .with_file(|file| file.keys().into_iter().find(|k| k.name() == name))?
+            .ok_or_else(|| PyValueError::new_err(format!("Key '{}' not found", name)))?;
+        if key.class_name() != class {
+            return Err(PyValueError::new_err(format!(
+                "Key '{}' holds a {}, not a {}",
+                name,
+                key.class_name(),
+                class
+            )));
+        }
+
+        // oxyroot keeps reading objects other than trees to itself, so read the
+        // key's record directly: its header gives the object's length and where
+        // it starts, and the rest is the object, compressed if it is shorter.
+        let mut file = File::open(self.local_path()?)?;
+        std::io::Seek::seek(&mut file, std::io::SeekFrom::Start(key.seek_key() as u64))?;
+        let mut record = vec![0u8; key.n_bytes().max(0) as usize];
+        std::io::Read::read_exact(&mut file, &mut record)?;
+        let corrupt = || PyValueError::new_err(format!("Key '{}' is corrupt", name));
+        let header = record.get(..16).ok_or_else(corrupt)?;
+        let obj_len = i32::from_be_bytes([header[6], header[7], header[8], header[9]]) as usize;
+        let key_len = i16::from_be_bytes([header[14], header[15]]) as usize;
+        let data = record.get(key_len..).ok_or_else(corrupt)?;
+        let data = if data.len() < obj_len {
+            root_decompress(data, obj_len)
+                .map_err(|e| PyValueError::new_err(format!("Cannot read key '{}': {}", name, e)))?
+        } else {
+            data.to_vec()
+        };
+        Ok((data, key_len as u32))
+    }
+
+    /// Drops the open file and cached trees; later calls reopen the file.
+    fn close(&self) {
+        self.trees.borrow_mut().clear();
+        self.leaf_lists.borrow_mut().clear();
+        self.file.borrow_mut().take();
+    }
+}
+
+#[pyclass(name = "RootFile", unsendable)]
 struct PyRootFile {
     #[pyo3(get)]
     path: String,
+    file: Rc<FileHandle>,
 }
 
-#[pyclass(name = "Tree")]
+#[pyclass(name = "Tree", unsendable)]
 struct PyTree {
     #[pyo3(get)]
     path: String,
     #[pyo3(get)]
     name: String,
+    file: Rc<FileHandle>,
+    /// Trees added with `add_friend()`, read alongside this one by entry.
+    friends: Vec<(Rc<FileHandle>, String)>,
+}
+
+/// The options of `Tree.arrays()`, so Rust callers can set the few they need
+/// and leave the rest at their Python defaults.
+struct ReadOptions<'a> {
+    columns: Option<Vec<String>>,
+    entry_start: Option<usize>,
+    entry_stop: Option<usize>,
+    strict: bool,
+    char_as_string: bool,
+    cut: Option<&'a str>,
+    aliases: Option<HashMap<String, String>>,
+    split_fixed_arrays: bool,
+    missing_values: Option<Vec<f64>>,
+    dtypes: Option<HashMap<String, String>>,
+    include_unsupported: bool,
+    enum_maps: Option<HashMap<String, HashMap<i64, String>>>,
+    drop_all_null: bool,
+    step: usize,
+    select_types: Option<Vec<String>>,
+}
+
+impl Default for ReadOptions<'_> {
+    fn default() -> Self {
+        ReadOptions {
+            columns: None,
+            entry_start: None,
+            entry_stop: None,
+            strict: false,
+            char_as_string: false,
+            cut: None,
+            aliases: None,
+            split_fixed_arrays: false,
+            missing_values: None,
+            dtypes: None,
+            include_unsupported: false,
+            enum_maps: None,
+            drop_all_null: false,
+            step: 1,
+            select_types: None,
+        }
+    }
 }
 
-#[pyclass(name = "Branch")]
+#[pyclass(name = "Branch", unsendable)]
 struct PyBranch {
     #[pyo3(get)]
     path: String,
@@ -37,48 +367,685 @@ struct PyBranch {
     tree_name: String,
     #[pyo3(get)]
     name: String,
+    file: Rc<FileHandle>,
+}
+
+#[pyclass(name = "MultiFile", unsendable)]
+struct PyMultiFile {
+    #[pyo3(get)]
+    paths: Vec<String>,
+    files: Vec<Rc<FileHandle>>,
+}
+
+/// The same tree read across several files, in file order.
+#[pyclass(name = "Chain", unsendable)]
+struct PyChain {
+    #[pyo3(get)]
+    name: String,
+    files: Vec<Rc<FileHandle>>,
 }
 
 #[pymethods]
 impl PyRootFile {
     #[new]
     fn new(path: String) -> Self {
-        PyRootFile { path }
+        PyRootFile {
+            file: Rc::new(FileHandle::new(path.clone())),
+            path,
+        }
     }
 
     fn keys(&self) -> PyResult<Vec<String>> {
-        let file = RootFile::open(&self.path).map_err(|e| PyValueError::new_err(e.to_string()))?;
-        Ok(file
-            .keys()
-            .into_iter()
-            .map(|k| k.name().to_string())
-            .collect())
+        self.file
+            .with_file(|file| file.keys_name().map(|k| k.to_string()).collect())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<RootFile '{}'>", self.path)
+    }
+
+    /// File-level metadata: the ROOT `version` that wrote the file (e.g.
+    /// 62406 for 6.24/06), its `compression_algorithm` and
+    /// `compression_level`, `size` in bytes and the number of top-level
+    /// `keys`.
+    fn info(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let (compression, keys) = self
+            .file
+            .with_file(|file| (file.compression(), file.keys().len()))?;
+        let path = self.file.local_path()?;
+        // oxyroot keeps the header version to itself; it follows the "root"
+        // magic, with 1000000 added for files over 2 GB.
+        let mut header = [0u8; 8];
+        std::io::Read::read_exact(&mut File::open(&path)?, &mut header)?;
+        let version = i32::from_be_bytes([header[4], header[5], header[6], header[7]]) % 1_000_000;
+
+        let info = PyDict::new(py);
+        info.set_item("version", version)?;
+        info.set_item(
+            "compression_algorithm",
+            compression_algorithm(compression / 100),
+        )?;
+        info.set_item("compression_level", compression % 100)?;
+        info.set_item("size", std::fs::metadata(&path)?.len())?;
+        info.set_item("keys", keys)?;
+        Ok(info.into())
+    }
+
+    /// Returns `(name, class name)` for every key, e.g. `("events", "TTree")`.
+    fn keys_with_types(&self) -> PyResult<Vec<(String, String)>> {
+        self.file.with_file(|file| {
+            file.keys()
+                .iter()
+                .map(|k| (k.name().to_string(), k.class_name().to_string()))
+                .collect()
+        })
+    }
+
+    /// The text of the `TObjString` stored under `name`, e.g. a run
+    /// configuration saved as JSON.
+    fn get_string(&self, name: &str) -> PyResult<String> {
+        let (data, key_len) = self.file.key_data(name, "TObjString")?;
+        let mut string = TObjString::default();
+        string
+            .unmarshal(&mut RBuffer::new(&data, key_len))
+            .map_err(|e| PyValueError::new_err(format!("Cannot read key '{}': {}", name, e)))?;
+        Ok(string.to_string())
+    }
+
+    /// Names of the keys holding a `TTree`, each listed once even if the file
+    /// holds several cycles of it.
+    fn trees(&self) -> PyResult<Vec<String>> {
+        self.file.with_file(|file| {
+            let mut names: Vec<String> = Vec::new();
+            for key in file.keys() {
+                if key.class_name() == "TTree" && !names.iter().any(|n| n == key.name()) {
+                    names.push(key.name().to_string());
+                }
+            }
+            names
+        })
+    }
+
+    fn close(&self) {
+        self.file.close();
+    }
+
+    fn __enter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        _exc_type: Option<&Bound<'_, PyAny>>,
+        _exc_value: Option<&Bound<'_, PyAny>>,
+        _traceback: Option<&Bound<'_, PyAny>>,
+    ) -> bool {
+        self.file.close();
+        false
     }
 
     fn __getitem__(&self, name: &str) -> PyResult<PyTree> {
         Ok(PyTree {
             path: self.path.clone(),
             name: name.to_string(),
+            file: self.file.clone(),
+            friends: Vec::new(),
         })
     }
+
+    /// The number of keys, as listed by `keys()`.
+    fn __len__(&self) -> PyResult<usize> {
+        Ok(self.keys()?.len())
+    }
+
+    fn __contains__(&self, name: &str) -> PyResult<bool> {
+        Ok(self.keys()?.iter().any(|key| key == name))
+    }
 }
 
 #[pymethods]
 impl PyTree {
+    /// Adds `tree_name` from `path` as a friend: `arrays()` then also reads
+    /// its branches, joined to this tree's by entry index. A branch name
+    /// both trees have is read from this tree.
+    fn add_friend(&mut self, path: String, tree_name: String) -> PyResult<()> {
+        let file = if path == self.path {
+            self.file.clone()
+        } else {
+            Rc::new(FileHandle::new(path))
+        };
+        let entries = self.file.tree(&self.name)?.entries();
+        let friend_entries = file.tree(&tree_name)?.entries();
+        if friend_entries != entries {
+            return Err(PyValueError::new_err(format!(
+                "Friend tree '{}' in '{}' has {} entries, expected {}",
+                tree_name, file.path, friend_entries, entries
+            )));
+        }
+        self.friends.push((file, tree_name));
+        Ok(())
+    }
+
     fn branches(&self) -> PyResult<Vec<String>> {
-        let mut file =
-            RootFile::open(&self.path).map_err(|e| PyValueError::new_err(e.to_string()))?;
-        let tree = file
-            .get_tree(&self.name)
-            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let tree = self.file.tree(&self.name)?;
         Ok(tree.branches().map(|b| b.name().to_string()).collect())
     }
 
+    /// Reads only the tree metadata, and leaves out the counts if the tree
+    /// can't be read or is in a remote file not downloaded yet.
+    fn __repr__(&self) -> String {
+        if !self.file.is_fetched() {
+            return format!("<Tree '{}' in '{}'>", self.name, self.path);
+        }
+        match self.file.tree(&self.name) {
+            Ok(tree) => format!(
+                "<Tree '{}' in '{}': {} branches, {} entries>",
+                self.name,
+                self.path,
+                tree.branches().count(),
+                tree.entries()
+            ),
+            Err(_) => format!("<Tree '{}' in '{}'>", self.name, self.path),
+        }
+    }
+
+    /// Trees compare by file path and name, so the same tree opened twice is
+    /// one dict key. Friends are not compared.
+    fn __eq__(&self, other: PyRef<Self>) -> bool {
+        (&self.path, &self.name) == (&other.path, &other.name)
+    }
+
+    fn __hash__(&self) -> u64 {
+        hash_key(&(&self.path, &self.name))
+    }
+
+    fn typenames(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let tree = self.file.tree(&self.name)?;
+        let typenames = PyDict::new(py);
+        for branch in tree.branches() {
+            typenames.set_item(branch.name(), branch.item_type_name())?;
+        }
+        Ok(typenames.into())
+    }
+
+    /// Checks every branch, sub-branches included, against the tree's entry
+    /// count and returns a message for each that disagrees, as in partially
+    /// written files. An empty list means the counts are consistent.
+    fn validate(&self) -> PyResult<Vec<String>> {
+        let tree = self.file.tree(&self.name)?;
+        Ok(tree
+            .branches_r()
+            .into_iter()
+            .filter_map(|branch| entries_mismatch(&tree, branch))
+            .collect())
+    }
+
+    /// Whether the tree has a branch called `name`.
+    fn has_branch(&self, name: &str) -> PyResult<bool> {
+        let tree = self.file.tree(&self.name)?;
+        Ok(tree.branch(name).is_some())
+    }
+
+    /// Maps each readable branch to the dtype `arrays()` returns for it, from
+    /// the branch types alone, and a leaf-list branch's leaves to theirs as
+    /// `branch.leaf` columns. Unsupported branches are left out.
+    fn schema(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let tree = self.file.tree(&self.name)?;
+        let leaf_lists = self.file.leaf_lists(&self.name)?;
+        let schema = PyDict::new(py);
+        for branch in tree.branches() {
+            if let Some(leaves) = leaf_lists.get(branch.name()) {
+                for leaf in leaves {
+                    schema.set_item(
+                        format!("{}.{}", branch.name(), leaf.name),
+                        leaf_dtype(leaf.code),
+                    )?;
+                }
+            } else if let Some(dtype) = dtype_name(&branch.item_type_name()) {
+                schema.set_item(branch.name(), dtype)?;
+            }
+        }
+        Ok(schema.into())
+    }
+
+    /// The tree's layout as a nested dict: its `entries`, the file's
+    /// `compression_algorithm` and `compression_level`, and `branches`, which
+    /// maps each branch to its `typename`, `class`, `dtype` (`None` if
+    /// unsupported), `leaf_count`, `entries` and own `branches`. Leaf-list
+    /// branches also map their `leaves`, as `branch.leaf` columns, to dtypes.
+    /// oxyroot doesn't expose per-branch compression, so it isn't listed.
+    fn describe(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let compression = self.file.with_file(|file| file.compression())?;
+        let tree = self.file.tree(&self.name)?;
+        let leaf_lists = self.file.leaf_lists(&self.name)?;
+
+        let info = PyDict::new(py);
+        info.set_item("name", &self.name)?;
+        info.set_item("entries", tree.entries())?;
+        info.set_item(
+            "compression_algorithm",
+            compression_algorithm(compression / 100),
+        )?;
+        info.set_item("compression_level", compression % 100)?;
+        let branches = PyDict::new(py);
+        for branch in tree.branches() {
+            let info = describe_branch(py, branch)?;
+            if let Some(leaves) = leaf_lists.get(branch.name()) {
+                let columns = PyDict::new(py);
+                for leaf in leaves {
+                    columns.set_item(
+                        format!("{}.{}", branch.name(), leaf.name),
+                        leaf_dtype(leaf.code),
+                    )?;
+                }
+                info.set_item("leaves", columns)?;
+            }
+            branches.set_item(branch.name(), info)?;
+        }
+        info.set_item("branches", branches)?;
+        Ok(info.into())
+    }
+
+    /// Estimates the bytes `arrays()` needs for the selected branches, from the
+    /// branch types and entry count alone. Variable-length and string branches
+    /// only count 8 bytes per entry, as their lengths are unknown before
+    /// reading, so the estimate is a lower bound for them. Unsupported
+    /// branches are left out.
+    #[pyo3(signature = (columns = None))]
+    fn size_estimate(&self, columns: Option<Vec<String>>) -> PyResult<usize> {
+        let tree = self.file.tree(&self.name)?;
+        let entries = tree.entries() as usize;
+        let branches_to_read = select_branches(
+            tree.branches().map(|b| b.name().to_string()).collect(),
+            columns,
+        )?;
+
+        if let Some(name) = branches_to_read.iter().find(|n| tree.branch(n).is_none()) {
+            return Err(PyValueError::new_err(format!(
+                "Branch '{}' not found",
+                name
+            )));
+        }
+        let leaves = self.file.leaf_lists(&self.name)?;
+        Ok(estimate_size(&tree, &leaves, &branches_to_read, entries))
+    }
+
+    /// The ROOT title of the tree, as stored in its key.
+    #[getter]
+    fn title(&self) -> PyResult<String> {
+        let title = self.file.with_file(|file| {
+            file.keys()
+                .iter()
+                .find(|k| k.name() == self.name)
+                .map(|k| k.title().to_string())
+        })?;
+        title.ok_or_else(|| PyValueError::new_err(format!("Tree '{}' not found", self.name)))
+    }
+
+    #[getter]
+    fn num_entries(&self) -> PyResult<usize> {
+        let tree = self.file.tree(&self.name)?;
+        Ok(tree.entries() as usize)
+    }
+
+    /// Reads the selected branches into a dict of arrays. `cut` is an
+    /// expression over branch names, e.g. `"(pt > 20) & (abs(eta) < 2.4)"`,
+    /// evaluated with numpy; only entries where it is true are returned.
+    /// `aliases` maps branch names to the keys used in the output. With
+    /// `split_fixed_arrays`, a `T[N]` branch `pos` becomes `pos_0`..`pos_{N-1}`.
+    /// Numeric columns holding any of `missing_values` are returned as
+    /// `numpy.ma.MaskedArray`s with those entries masked. `dtypes` maps
+    /// branch names to the dtype (as reported by `schema()`) to cast them to.
+    /// With `include_unsupported`, branches of unsupported types are returned,
+    /// after the others, as lists of `None` instead of being skipped.
+    /// `enum_maps` maps integer branches to `{value: label}` dicts; those
+    /// branches are returned as lists of labels, `None` where none is given.
+    /// With `drop_all_null`, columns whose every entry is null (`None` or
+    /// masked) are left out. `step` reads only every `step`-th entry from
+    /// `entry_start` on, with `cut` applied to those. `select_types` keeps only
+    /// the selected branches whose C++ type, e.g. `"float"` or `"double"`, is
+    /// in the list.
+    #[pyo3(signature = (columns = None, entry_start = None, entry_stop = None, strict = false, char_as_string = false, cut = None, aliases = None, split_fixed_arrays = false, missing_values = None, dtypes = None, include_unsupported = false, enum_maps = None, drop_all_null = false, step = 1, select_types = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn arrays(
+        &self,
+        py: Python,
+        columns: Option<Vec<String>>,
+        entry_start: Option<usize>,
+        entry_stop: Option<usize>,
+        strict: bool,
+        char_as_string: bool,
+        cut: Option<&str>,
+        aliases: Option<HashMap<String, String>>,
+        split_fixed_arrays: bool,
+        missing_values: Option<Vec<f64>>,
+        dtypes: Option<HashMap<String, String>>,
+        include_unsupported: bool,
+        enum_maps: Option<HashMap<String, HashMap<i64, String>>>,
+        drop_all_null: bool,
+        step: usize,
+        select_types: Option<Vec<String>>,
+    ) -> PyResult<Py<PyDict>> {
+        if step == 0 {
+            return Err(PyValueError::new_err("step must be greater than 0"));
+        }
+        let tree = self.file.tree(&self.name)?;
+        let friends = self
+            .friends
+            .iter()
+            .map(|(file, name)| file.tree(name))
+            .collect::<PyResult<Vec<_>>>()?;
+        let leaf_lists = std::iter::once(self.file.leaf_lists(&self.name))
+            .chain(
+                self.friends
+                    .iter()
+                    .map(|(file, name)| file.leaf_lists(name)),
+            )
+            .collect::<PyResult<Vec<_>>>()?;
+        let trees = std::iter::once(&tree)
+            .chain(&friends)
+            .map(Rc::as_ref)
+            .collect::<Vec<_>>();
+        let range = entry_range(tree.entries() as usize, entry_start, entry_stop)?;
+        let entries = range.len().div_ceil(step);
+        let mut cut = cut
+            .map(|cut| Cut::new(py, &trees, cut, range.start).map(|cut| cut.step_by(step)))
+            .transpose()?;
+        let mut unsupported = Vec::new();
+        let mut iters = Vec::new();
+        let mut needed = 0;
+        for ((tree, columns), leaves) in trees
+            .iter()
+            .zip(split_columns(&trees, columns)?)
+            .zip(&leaf_lists)
+        {
+            let columns = match &select_types {
+                Some(types) => Some(
+                    select_branches(
+                        tree.branches().map(|b| b.name().to_string()).collect(),
+                        columns,
+                    )?
+                    .into_iter()
+                    .filter(|name| {
+                        tree.branch(name)
+                            .is_some_and(|branch| types.contains(&branch.item_type_name()))
+                    })
+                    .collect(),
+                ),
+                None => columns,
+            };
+            let columns = if include_unsupported {
+                let selected = select_branches(
+                    tree.branches().map(|b| b.name().to_string()).collect(),
+                    columns,
+                )?;
+                let (readable, rest): (Vec<_>, Vec<_>) = selected.into_iter().partition(|name| {
+                    leaves.contains_key(name)
+                        || tree.branch(name).is_none_or(|branch| {
+                            !matches!(ColumnIter::new(branch, 0, char_as_string), Ok(None))
+                        })
+                });
+                unsupported.extend(rest);
+                Some(readable)
+            } else {
+                columns
+            };
+            let selected = select_branches(
+                tree.branches().map(|b| b.name().to_string()).collect(),
+                columns.clone(),
+            )?;
+            needed += estimate_size(tree, leaves, &selected, entries);
+            iters.extend(
+                column_iters(
+                    tree,
+                    columns,
+                    range.start,
+                    strict,
+                    char_as_string,
+                    split_fixed_arrays,
+                    leaves,
+                )?
+                .into_iter()
+                .map(|(name, iter)| (name, iter.step_by(step))),
+            );
+        }
+        check_memory_limit(needed)?;
+
+        let mut columns = Vec::new();
+        let mut rows = entries;
+        match cut.as_mut() {
+            None => {
+                for (name, iter) in iters.iter_mut() {
+                    py.check_signals()?;
+                    columns.push((name.as_str(), iter.next_batch(py, entries)?));
+                }
+            }
+            Some(cut) => {
+                let mut parts = iters.iter().map(|_| Vec::new()).collect::<Vec<_>>();
+                let mut remaining = entries;
+                rows = 0;
+                loop {
+                    py.check_signals()?;
+                    let n = remaining.min(DEFAULT_ROW_GROUP_SIZE);
+                    let (mask, keep) = cut.next_mask(py, n)?;
+                    rows += keep.iter().filter(|&&keep| keep).count();
+                    for ((_, iter), part) in iters.iter_mut().zip(parts.iter_mut()) {
+                        part.push(apply_mask(py, iter.next_batch(py, n)?, &mask, &keep)?);
+                    }
+                    remaining -= n;
+                    if remaining == 0 {
+                        break;
+                    }
+                }
+                for ((name, _), parts) in iters.iter().zip(parts) {
+                    columns.push((name.as_str(), concatenate(py, parts)?));
+                }
+            }
+        }
+
+        let arrays = PyDict::new(py);
+        for (name, column) in columns {
+            let column = match dtypes.as_ref().and_then(|dtypes| dtypes.get(name)) {
+                Some(dtype) => cast_numpy(py, column, name, dtype)?,
+                None => column,
+            };
+            let column = match enum_maps.as_ref().and_then(|maps| maps.get(name)) {
+                Some(labels) => label_enum(py, column, name, labels)?,
+                None => column,
+            };
+            let column = match &missing_values {
+                Some(missing_values) => mask_missing(py, column, missing_values)?,
+                None => column,
+            };
+            if drop_all_null && is_all_null(py, column.bind(py))? {
+                continue;
+            }
+            let name = aliases
+                .as_ref()
+                .and_then(|aliases| aliases.get(name))
+                .map_or(name, String::as_str);
+            arrays.set_item(name, column)?;
+        }
+        // Unsupported branches are all null, unless there are no entries.
+        for name in unsupported.iter().filter(|_| !drop_all_null || rows == 0) {
+            let name = aliases
+                .as_ref()
+                .and_then(|aliases| aliases.get(name))
+                .unwrap_or(name);
+            arrays.set_item(name, PyList::new(py, (0..rows).map(|_| py.None()))?)?;
+        }
+        Ok(arrays.into())
+    }
+
+    /// Computes `expression`, e.g. `"np.sqrt(px*px + py*py)"`, over the
+    /// branches it names, the way `cut` is evaluated, and returns one value
+    /// per entry as a numpy array. `columns_needed` lists the branches to
+    /// read when they can't be found from the names in the expression.
+    #[pyo3(signature = (expression, columns_needed = None, entry_start = None, entry_stop = None))]
+    fn evaluate(
+        &self,
+        py: Python,
+        expression: &str,
+        columns_needed: Option<Vec<String>>,
+        entry_start: Option<usize>,
+        entry_stop: Option<usize>,
+    ) -> PyResult<Py<PyAny>> {
+        let tree = self.file.tree(&self.name)?;
+        let friends = self
+            .friends
+            .iter()
+            .map(|(file, name)| file.tree(name))
+            .collect::<PyResult<Vec<_>>>()?;
+        let trees = std::iter::once(&tree)
+            .chain(&friends)
+            .map(Rc::as_ref)
+            .collect::<Vec<_>>();
+        let range = entry_range(tree.entries() as usize, entry_start, entry_stop)?;
+        let mut expression =
+            Cut::with_branches(py, &trees, expression, range.start, columns_needed)?;
+        let needed = expression
+            .iters
+            .iter()
+            .filter_map(|(name, _)| {
+                let tree = trees.iter().find(|tree| tree.branch(name).is_some())?;
+                Some(estimate_size(
+                    tree,
+                    &HashMap::new(),
+                    std::slice::from_ref(name),
+                    range.len(),
+                ))
+            })
+            .sum();
+        check_memory_limit(needed)?;
+
+        let values = expression.next_values(py, range.len())?;
+        let numpy = py.import("numpy")?;
+        let values = numpy.call_method1("asarray", (values,))?;
+        Ok(numpy
+            .call_method1("broadcast_to", (values, range.len()))?
+            .call_method0("copy")?
+            .unbind())
+    }
+
+    /// Reads the selected branches into a `pandas.DataFrame`. `ignore_columns`
+    /// takes the same names or patterns as `columns` and drops them.
+    #[pyo3(signature = (columns = None, ignore_columns = None, entry_start = None, entry_stop = None, strict = false))]
+    fn to_pandas(
+        &self,
+        py: Python,
+        columns: Option<Vec<String>>,
+        ignore_columns: Option<Vec<String>>,
+        entry_start: Option<usize>,
+        entry_stop: Option<usize>,
+        strict: bool,
+    ) -> PyResult<Py<PyAny>> {
+        let mut all = self.branches()?;
+        for (file, name) in &self.friends {
+            for branch in file.tree(name)?.branches() {
+                if !all.iter().any(|name| name == branch.name()) {
+                    all.push(branch.name().to_string());
+                }
+            }
+        }
+        let columns = select_ignoring(all, columns, ignore_columns)?;
+
+        let arrays = self.read_arrays(
+            py,
+            ReadOptions {
+                columns: Some(columns),
+                entry_start,
+                entry_stop,
+                strict,
+                ..Default::default()
+            },
+        )?;
+        let pandas = py.import("pandas")?;
+        Ok(pandas.call_method1("DataFrame", (arrays,))?.unbind())
+    }
+
+    /// Reads the selected branches into one numpy structured array with a
+    /// field per branch, like uproot's `library="np"`. Strings and jagged
+    /// branches become object fields.
+    #[pyo3(signature = (columns = None, entry_start = None, entry_stop = None, strict = false))]
+    fn to_structured_array(
+        &self,
+        py: Python,
+        columns: Option<Vec<String>>,
+        entry_start: Option<usize>,
+        entry_stop: Option<usize>,
+        strict: bool,
+    ) -> PyResult<Py<PyAny>> {
+        let tree = self.file.tree(&self.name)?;
+        let range = entry_range(tree.entries() as usize, entry_start, entry_stop)?;
+        let arrays = self.read_arrays(
+            py,
+            ReadOptions {
+                columns,
+                entry_start,
+                entry_stop,
+                strict,
+                ..Default::default()
+            },
+        )?;
+
+        let numpy = py.import("numpy")?;
+        let mut fields = Vec::new();
+        let mut columns = Vec::new();
+        for (name, column) in arrays.bind(py).iter() {
+            let column = if column.is_instance_of::<PyList>() {
+                let kwargs = PyDict::new(py);
+                kwargs.set_item("dtype", "object")?;
+                kwargs.set_item("count", range.len())?;
+                numpy.call_method("fromiter", (column,), Some(&kwargs))?
+            } else {
+                column
+            };
+            fields.push((name.clone(), column.getattr("dtype")?));
+            columns.push((name, column));
+        }
+        let records = numpy.call_method1("empty", (range.len(), fields))?;
+        for (name, column) in columns {
+            records.set_item(name, column)?;
+        }
+        Ok(records.unbind())
+    }
+
+    /// Reads the entries at `indices` of the selected branches, in that order,
+    /// as a dict of arrays like `arrays()`.
+    #[pyo3(signature = (indices, columns = None, strict = false))]
+    fn take(
+        &self,
+        py: Python,
+        indices: Vec<i64>,
+        columns: Option<Vec<String>>,
+        strict: bool,
+    ) -> PyResult<Py<PyDict>> {
+        let tree = self.file.tree(&self.name)?;
+        let (offsets, range) = take_range(&indices, tree.entries() as usize)?;
+        let selected = select_branches(
+            tree.branches().map(|b| b.name().to_string()).collect(),
+            columns.clone(),
+        )?;
+        let leaves = self.file.leaf_lists(&self.name)?;
+        // The entries between the first and last index are all read.
+        check_memory_limit(estimate_size(&tree, &leaves, &selected, range.len()))?;
+
+        let arrays = PyDict::new(py);
+        for (name, mut iter) in
+            column_iters(&tree, columns, range.start, strict, false, false, &leaves)?
+        {
+            let array = iter.next_batch(py, range.len())?;
+            arrays.set_item(name, take_entries(py, array, &offsets)?)?;
+        }
+        Ok(arrays.into())
+    }
+
     fn __getitem__(&self, name: &str) -> PyResult<PyBranch> {
         Ok(PyBranch {
             path: self.path.clone(),
             tree_name: self.name.clone(),
             name: name.to_string(),
+            file: self.file.clone(),
         })
     }
 
@@ -90,127 +1057,447 @@ impl PyTree {
                 path: slf.path.clone(),
                 tree_name: slf.name.clone(),
                 branches: branches.into_iter(),
+                file: slf.file.clone(),
             },
         )
     }
 
-    #[pyo3(signature = (output_file, overwrite = false, compression = "snappy", columns = None))]
+    /// Yields `(name, array)` for each selected branch, reading one branch
+    /// per step. A leaf-list branch yields each of its `branch.leaf` columns.
+    #[pyo3(signature = (columns = None, strict = false))]
+    fn iter_arrays(&self, columns: Option<Vec<String>>, strict: bool) -> PyResult<PyArrayIterator> {
+        let tree = self.file.tree(&self.name)?;
+        let branches = select_branches(
+            tree.branches().map(|b| b.name().to_string()).collect(),
+            columns,
+        )?;
+        Ok(PyArrayIterator {
+            branches: branches.into_iter(),
+            strict,
+            leaves: self.file.leaf_lists(&self.name)?,
+            pending: Vec::new(),
+            tree,
+        })
+    }
+
+    #[pyo3(signature = (batch_size, columns = None, strict = false))]
+    fn iter_batches(
+        &self,
+        batch_size: usize,
+        columns: Option<Vec<String>>,
+        strict: bool,
+    ) -> PyResult<PyBatchIterator> {
+        if batch_size == 0 {
+            return Err(PyValueError::new_err("batch_size must be greater than 0"));
+        }
+
+        PyBatchIterator::new(
+            self.file.tree(&self.name)?,
+            &*self.file.leaf_lists(&self.name)?,
+            batch_size,
+            columns,
+            strict,
+        )
+    }
+
+    /// Reads the selected branches into a `pyarrow.Table`, handed over through
+    /// the Arrow C stream interface without copying. With
+    /// `string_as_categorical`, string columns are dictionary-encoded.
+    #[pyo3(signature = (columns = None, entry_start = None, entry_stop = None, strict = false, string_as_categorical = false))]
+    fn to_arrow(
+        &self,
+        py: Python,
+        columns: Option<Vec<String>>,
+        entry_start: Option<usize>,
+        entry_stop: Option<usize>,
+        strict: bool,
+        string_as_categorical: bool,
+    ) -> PyResult<Py<PyAny>> {
+        let tree = self.file.tree(&self.name)?;
+        let range = entry_range(tree.entries() as usize, entry_start, entry_stop)?;
+        let selected = select_branches(
+            tree.branches().map(|b| b.name().to_string()).collect(),
+            columns.clone(),
+        )?;
+        let leaves = self.file.leaf_lists(&self.name)?;
+        check_memory_limit(estimate_size(&tree, &leaves, &selected, range.len()))?;
+        let mut iters = column_iters(&tree, columns, range.start, strict, false, false, &leaves)?;
+        let mut batch = next_record_batch(&mut iters, range.len(), true)?;
+        if string_as_categorical {
+            batch = dictionary_strings(batch)?;
+        }
+
+        let schema = batch.schema();
+        let reader = RecordBatchIterator::new(vec![Ok(batch)], schema);
+        let mut stream = FFI_ArrowArrayStream::new(Box::new(reader));
+        let pyarrow = py.import("pyarrow")?;
+        // pyarrow moves the stream out of `stream`, leaving it released.
+        let reader = pyarrow.getattr("RecordBatchReader")?.call_method1(
+            "_import_from_c",
+            (&mut stream as *mut FFI_ArrowArrayStream as usize,),
+        )?;
+        Ok(reader.call_method0("read_all")?.unbind())
+    }
+
+    /// Writes the selected branches to a parquet file. With `summary`, returns
+    /// a dict mapping each column to its `codec`, `uncompressed_bytes`,
+    /// `compressed_bytes` and their `ratio`, to tell how well it compressed.
+    #[pyo3(signature = (output_file, overwrite = false, compression = "snappy", columns = None, entry_start = None, entry_stop = None, nullable = true, strict = false, row_group_size = DEFAULT_ROW_GROUP_SIZE, compression_level = None, cut = None, aliases = None, split_fixed_arrays = false, missing_values = None, dtypes = None, string_as_categorical = false, dictionary_enabled = None, data_page_size_limit = None, write_batch_size = None, ignore_columns = None, summary = false))]
+    #[allow(clippy::too_many_arguments)]
     fn to_parquet(
         &self,
+        py: Python,
         output_file: String,
         overwrite: bool,
         compression: &str,
         columns: Option<Vec<String>>,
-    ) -> PyResult<()> {
+        entry_start: Option<usize>,
+        entry_stop: Option<usize>,
+        nullable: bool,
+        strict: bool,
+        row_group_size: usize,
+        compression_level: Option<i32>,
+        cut: Option<&str>,
+        aliases: Option<HashMap<String, String>>,
+        split_fixed_arrays: bool,
+        missing_values: Option<Vec<f64>>,
+        dtypes: Option<HashMap<String, String>>,
+        string_as_categorical: bool,
+        dictionary_enabled: Option<bool>,
+        data_page_size_limit: Option<usize>,
+        write_batch_size: Option<usize>,
+        ignore_columns: Option<Vec<String>>,
+        summary: bool,
+    ) -> PyResult<Option<Py<PyDict>>> {
+        if row_group_size == 0 {
+            return Err(PyValueError::new_err(
+                "row_group_size must be greater than 0",
+            ));
+        }
+        if data_page_size_limit == Some(0) || write_batch_size == Some(0) {
+            return Err(PyValueError::new_err(
+                "data_page_size_limit and write_batch_size must be greater than 0",
+            ));
+        }
         if !overwrite && Path::new(&output_file).exists() {
             return Err(PyValueError::new_err("File exists, use overwrite=True"));
         }
 
-        let compression = match compression {
-            "snappy" => Compression::SNAPPY,
-            "uncompressed" => Compression::UNCOMPRESSED,
-            "gzip" => Compression::GZIP(GzipLevel::default()),
-            "lzo" => Compression::LZO,
-            "brotli" => Compression::BROTLI(BrotliLevel::default()),
-            "lz4" => Compression::LZ4,
-            "zstd" => Compression::ZSTD(ZstdLevel::default()),
-            _ => return Err(PyValueError::new_err("Invalid compression type")),
-        };
-
-        let mut file =
-            RootFile::open(&self.path).map_err(|e| PyValueError::new_err(e.to_string()))?;
-        let tree = file
-            .get_tree(&self.name)
-            .map_err(|e| PyValueError::new_err(e.to_string()))?;
-
-        let mut fields = Vec::new();
-        let mut arrays = Vec::new();
+        let codec = compression;
+        let compression = parquet_compression(compression, compression_level)?;
 
-        let branches_to_save = if let Some(columns) = columns {
-            columns
-        } else {
-            tree.branches().map(|b| b.name().to_string()).collect()
+        let tree = self.file.tree(&self.name)?;
+        let range = entry_range(tree.entries() as usize, entry_start, entry_stop)?;
+        let columns = match ignore_columns {
+            Some(ignore_columns) => Some(select_ignoring(
+                tree.branches().map(|b| b.name().to_string()).collect(),
+                columns,
+                Some(ignore_columns),
+            )?),
+            None => columns,
         };
 
-        for branch_name in branches_to_save {
-            let branch = match tree.branch(&branch_name) {
-                Some(branch) => branch,
-                None => {
-                    println!("Branch '{}' not found, skipping", branch_name);
-                    continue;
-                }
-            };
+        let mut cut = cut
+            .map(|cut| Cut::new(py, &[&tree], cut, range.start))
+            .transpose()?;
+        let leaves = self.file.leaf_lists(&self.name)?;
+        let iters = branch_column_iters(
+            &tree,
+            columns,
+            range.start,
+            false,
+            split_fixed_arrays,
+            &leaves,
+            &mut |message| skip_branch(strict, message),
+        )?;
+        let path = self.file.local_path()?.to_string_lossy().into_owned();
+        // Missing values are written as nulls, which need nullable columns.
+        let nullable = nullable || missing_values.is_some();
+        let mut props = WriterProperties::builder()
+            .set_compression(compression)
+            .set_max_row_group_size(row_group_size);
+        if let Some(dictionary_enabled) = dictionary_enabled {
+            props = props.set_dictionary_enabled(dictionary_enabled);
+        }
+        if let Some(data_page_size_limit) = data_page_size_limit {
+            props = props.set_data_page_size_limit(data_page_size_limit);
+        }
+        if let Some(write_batch_size) = write_batch_size {
+            props = props.set_write_batch_size(write_batch_size);
+        }
+        let props = props.build();
 
-            let (field, array) = match branch.item_type_name().as_str() {
-                "float" => {
-                    let data = branch.as_iter::<f32>().unwrap().collect::<Vec<_>>();
-                    let array: ArrayRef = Arc::new(Float32Array::from(data));
-                    (Field::new(&branch_name, DataType::Float32, false), array)
-                }
-                "double" => {
-                    let data = branch.as_iter::<f64>().unwrap().collect::<Vec<_>>();
-                    let array: ArrayRef = Arc::new(Float64Array::from(data));
-                    (Field::new(&branch_name, DataType::Float64, false), array)
-                }
-                "int32_t" => {
-                    let data = branch.as_iter::<i32>().unwrap().collect::<Vec<_>>();
-                    let array: ArrayRef = Arc::new(Int32Array::from(data));
-                    (Field::new(&branch_name, DataType::Int32, false), array)
-                }
-                "int64_t" => {
-                    let data = branch.as_iter::<i64>().unwrap().collect::<Vec<_>>();
-                    let array: ArrayRef = Arc::new(Int64Array::from(data));
-                    (Field::new(&branch_name, DataType::Int64, false), array)
-                }
-                "uint32_t" => {
-                    let data = branch.as_iter::<u32>().unwrap().collect::<Vec<_>>();
-                    let array: ArrayRef = Arc::new(UInt32Array::from(data));
-                    (Field::new(&branch_name, DataType::UInt32, false), array)
-                }
-                "uint64_t" => {
-                    let data = branch.as_iter::<u64>().unwrap().collect::<Vec<_>>();
-                    let array: ArrayRef = Arc::new(UInt64Array::from(data));
-                    (Field::new(&branch_name, DataType::UInt64, false), array)
-                }
-                "string" => {
-                    let data = branch.as_iter::<String>().unwrap().collect::<Vec<_>>();
-                    let array: ArrayRef = Arc::new(StringArray::from(data));
-                    (Field::new(&branch_name, DataType::Utf8, false), array)
-                }
-                other => {
-                    println!("Unsupported branch type: {}, skipping", other);
-                    continue;
+        // Write one row group at a time so only `row_group_size` entries of
+        // each branch are held in memory. The writer takes its schema from the
+        // first batch once dtypes and aliases are applied. Branches are read
+        // on `set_num_threads()` threads, the batches built here in order.
+        std::thread::scope(|scope| -> PyResult<Option<Py<PyDict>>> {
+            let mut source = BatchSource::new(
+                scope,
+                iters,
+                match range.len() < PARALLEL_MIN_ENTRIES {
+                    true => 1,
+                    false => num_threads(),
+                },
+                path,
+                &self.name,
+                range.start,
+                split_fixed_arrays,
+            );
+            let mut writer = None;
+            let mut remaining = range.len();
+            loop {
+                py.check_signals()?;
+                let mut batch = source.next_batch(py, remaining.min(row_group_size), nullable)?;
+                let n = batch.num_rows();
+                if let Some(cut) = cut.as_mut() {
+                    let (_, keep) = cut.next_mask(py, n)?;
+                    batch = filter_record_batch(&batch, &BooleanArray::from(keep))
+                        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+                }
+                if let Some(dtypes) = &dtypes {
+                    batch = cast_columns(batch, dtypes)?;
+                }
+                if string_as_categorical {
+                    batch = dictionary_strings(batch)?;
+                }
+                if let Some(missing_values) = &missing_values {
+                    batch = null_missing(batch, missing_values)?;
+                }
+                if let Some(aliases) = &aliases {
+                    batch = rename_columns(batch, aliases)?;
                 }
-            };
-            fields.push(field);
-            arrays.push(array);
-        }
 
-        let schema = Arc::new(Schema::new(fields));
-        let props = WriterProperties::builder()
-            .set_compression(compression)
-            .build();
-        let batch = RecordBatch::try_new(schema.clone(), arrays).unwrap();
+                if writer.is_none() {
+                    let file = File::create(&output_file)?;
+                    writer = Some(
+                        ArrowWriter::try_new(file, batch.schema(), Some(props.clone()))
+                            .map_err(|e| PyValueError::new_err(e.to_string()))?,
+                    );
+                }
+                let writer = writer.as_mut().unwrap();
+                writer
+                    .write(&batch)
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?;
+                remaining -= n;
+                if remaining == 0 || n == 0 {
+                    break;
+                }
+            }
+            let metadata = writer
+                .expect("the first batch creates the writer")
+                .close()
+                .map_err(|e| PyValueError::new_err(e.to_string()))?;
 
-        let file = File::create(output_file)?;
-        let mut writer = ArrowWriter::try_new(file, schema, Some(props))
-            .map_err(|e| PyValueError::new_err(e.to_string()))?;
-        writer
-            .write(&batch)
-            .map_err(|e| PyValueError::new_err(e.to_string()))?;
-        writer
-            .close()
-            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+            if !summary {
+                return Ok(None);
+            }
+            Ok(Some(parquet_summary(py, &metadata, codec)?.unbind()))
+        })
+    }
+}
 
-        Ok(())
+impl PyTree {
+    /// `arrays()` with its options in a `ReadOptions`, for Rust callers.
+    fn read_arrays(&self, py: Python, options: ReadOptions) -> PyResult<Py<PyDict>> {
+        let ReadOptions {
+            columns,
+            entry_start,
+            entry_stop,
+            strict,
+            char_as_string,
+            cut,
+            aliases,
+            split_fixed_arrays,
+            missing_values,
+            dtypes,
+            include_unsupported,
+            enum_maps,
+            drop_all_null,
+            step,
+            select_types,
+        } = options;
+        self.arrays(
+            py,
+            columns,
+            entry_start,
+            entry_stop,
+            strict,
+            char_as_string,
+            cut,
+            aliases,
+            split_fixed_arrays,
+            missing_values,
+            dtypes,
+            include_unsupported,
+            enum_maps,
+            drop_all_null,
+            step,
+            select_types,
+        )
     }
 }
 
-#[pyclass]
+/// One parquet file written from several trees, one `write_tree()` call at a
+/// time, so many ROOT sources end up in a single file without a separate
+/// concatenation step.
+#[pyclass(name = "ParquetWriter", unsendable)]
+struct PyParquetWriter {
+    #[pyo3(get)]
+    path: String,
+    props: WriterProperties,
+    row_group_size: usize,
+    /// Created with the schema of the first tree written.
+    writer: Option<ArrowWriter<File>>,
+    schema: Option<SchemaRef>,
+    closed: bool,
+}
+
+#[pymethods]
+impl PyParquetWriter {
+    #[new]
+    #[pyo3(signature = (output_file, overwrite = false, compression = "snappy", compression_level = None, row_group_size = DEFAULT_ROW_GROUP_SIZE))]
+    fn new(
+        output_file: String,
+        overwrite: bool,
+        compression: &str,
+        compression_level: Option<i32>,
+        row_group_size: usize,
+    ) -> PyResult<Self> {
+        if row_group_size == 0 {
+            return Err(PyValueError::new_err(
+                "row_group_size must be greater than 0",
+            ));
+        }
+        if !overwrite && Path::new(&output_file).exists() {
+            return Err(PyValueError::new_err("File exists, use overwrite=True"));
+        }
+        let props = WriterProperties::builder()
+            .set_compression(parquet_compression(compression, compression_level)?)
+            .set_max_row_group_size(row_group_size)
+            .build();
+        Ok(PyParquetWriter {
+            path: output_file,
+            props,
+            row_group_size,
+            writer: None,
+            schema: None,
+            closed: false,
+        })
+    }
+
+    /// Appends the selected branches of `tree` as further row groups, reading
+    /// one row group at a time, and returns the number of entries written.
+    /// Every tree must give the same columns and types as the first one.
+    #[pyo3(signature = (tree, columns = None, entry_start = None, entry_stop = None, strict = false))]
+    fn write_tree(
+        &mut self,
+        py: Python,
+        tree: PyRef<PyTree>,
+        columns: Option<Vec<String>>,
+        entry_start: Option<usize>,
+        entry_stop: Option<usize>,
+        strict: bool,
+    ) -> PyResult<usize> {
+        if self.closed {
+            return Err(PyValueError::new_err("ParquetWriter is closed"));
+        }
+        let reader_tree = tree.file.tree(&tree.name)?;
+        let range = entry_range(reader_tree.entries() as usize, entry_start, entry_stop)?;
+        let leaves = tree.file.leaf_lists(&tree.name)?;
+        let mut iters = column_iters(
+            &reader_tree,
+            columns,
+            range.start,
+            strict,
+            false,
+            false,
+            &leaves,
+        )?;
+
+        let mut remaining = range.len();
+        loop {
+            py.check_signals()?;
+            let batch = next_record_batch(&mut iters, remaining.min(self.row_group_size), true)?;
+            let n = batch.num_rows();
+            let writer = match self.writer.as_mut() {
+                Some(writer) => {
+                    if self.schema.as_ref().map(|s| s.fields()) != Some(batch.schema().fields()) {
+                        return Err(PyValueError::new_err(format!(
+                            "Tree '{}' in '{}' has other columns or types than those \
+                             already written to '{}'",
+                            tree.name, tree.path, self.path
+                        )));
+                    }
+                    writer
+                }
+                None => {
+                    self.schema = Some(batch.schema());
+                    self.writer.insert(
+                        ArrowWriter::try_new(
+                            File::create(&self.path)?,
+                            batch.schema(),
+                            Some(self.props.clone()),
+                        )
+                        .map_err(|e| PyValueError::new_err(e.to_string()))?,
+                    )
+                }
+            };
+            writer
+                .write(&batch)
+                .map_err(|e| PyValueError::new_err(e.to_string()))?;
+            // Flush here, so row groups don't span trees.
+            writer
+                .flush()
+                .map_err(|e| PyValueError::new_err(e.to_string()))?;
+            remaining -= n;
+            if remaining == 0 || n == 0 {
+                break;
+            }
+        }
+        Ok(range.len())
+    }
+
+    /// Writes the parquet footer. Until then the file is incomplete; if no
+    /// tree was written, no file is created.
+    fn close(&mut self) -> PyResult<()> {
+        self.closed = true;
+        if let Some(writer) = self.writer.take() {
+            writer
+                .close()
+                .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    fn __enter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    fn __exit__(
+        &mut self,
+        _exc_type: Option<&Bound<'_, PyAny>>,
+        _exc_value: Option<&Bound<'_, PyAny>>,
+        _traceback: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<bool> {
+        self.close()?;
+        Ok(false)
+    }
+}
+
+impl Drop for PyParquetWriter {
+    /// Closes a writer that was never closed, so the file stays readable.
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+#[pyclass(unsendable)]
 struct PyBranchIterator {
     path: String,
     tree_name: String,
     branches: std::vec::IntoIter<String>,
+    file: Rc<FileHandle>,
 }
 
 #[pymethods]
@@ -224,96 +1511,3368 @@ impl PyBranchIterator {
             path: self.path.clone(),
             tree_name: self.tree_name.clone(),
             name,
+            file: self.file.clone(),
         })
     }
 }
 
-#[pymethods]
-impl PyBranch {
-    fn array(&self, py: Python) -> PyResult<Py<PyAny>> {
-        let mut file =
-            RootFile::open(&self.path).map_err(|e| PyValueError::new_err(e.to_string()))?;
-        let tree = file
-            .get_tree(&self.tree_name)
-            .map_err(|e| PyValueError::new_err(e.to_string()))?;
-        let branch = tree
-            .branch(&self.name)
-            .ok_or_else(|| PyValueError::new_err("Branch not found"))?;
+/// A Python expression over branch names, used as a row filter by `cut` and
+/// computed by `evaluate()`. The branches it uses are read alongside the
+/// output columns, one batch at a time, and the expression is evaluated on
+/// them with numpy.
+struct Cut<'a> {
+    code: Py<PyAny>,
+    globals: Py<PyDict>,
+    iters: Vec<(String, ColumnIter<'a>)>,
+}
+
+impl<'a> Cut<'a> {
+    fn new(py: Python, trees: &[&'a ReaderTree], expression: &str, start: usize) -> PyResult<Self> {
+        Self::with_branches(py, trees, expression, start, None)
+    }
+
+    /// Like `new()`, but reads exactly `branches` if given, rather than the
+    /// names the expression uses that are branches.
+    fn with_branches(
+        py: Python,
+        trees: &[&'a ReaderTree],
+        expression: &str,
+        start: usize,
+        branches: Option<Vec<String>>,
+    ) -> PyResult<Self> {
+        let builtins = py.import("builtins")?;
+        let code = builtins.call_method1("compile", (expression, "<cut>", "eval"))?;
+
+        let explicit = branches.is_some();
+        let names = match branches {
+            Some(branches) => branches,
+            None => code.getattr("co_names")?.extract::<Vec<String>>()?,
+        };
+        let mut iters = Vec::new();
+        for name in names {
+            let Some(branch) = trees.iter().find_map(|tree| tree.branch(&name)) else {
+                if explicit {
+                    return Err(PyValueError::new_err(format!(
+                        "Branch '{}' not found",
+                        name
+                    )));
+                }
+                continue;
+            };
+            match ColumnIter::new(branch, start, false)? {
+                Some(iter) => iters.push((name, iter)),
+                None => return Err(PyValueError::new_err(unsupported_branch(branch))),
+            }
+        }
+
+        let globals = PyDict::new(py);
+        globals.set_item("np", py.import("numpy")?)?;
+        Ok(Cut {
+            code: code.unbind(),
+            globals: globals.unbind(),
+            iters,
+        })
+    }
+
+    /// Keeps only every `step`-th entry, like `ColumnIter::step_by()`.
+    fn step_by(self, step: usize) -> Self {
+        Cut {
+            iters: self
+                .iters
+                .into_iter()
+                .map(|(name, iter)| (name, iter.step_by(step)))
+                .collect(),
+            ..self
+        }
+    }
 
-        match branch.item_type_name().as_str() {
-            "float" => {
-                let data = branch
+    /// Evaluates the expression on the next `n` entries.
+    fn next_values<'py>(&mut self, py: Python<'py>, n: usize) -> PyResult<Bound<'py, PyAny>> {
+        let locals = PyDict::new(py);
+        for (name, iter) in self.iters.iter_mut() {
+            locals.set_item(name.as_str(), iter.next_batch(py, n)?)?;
+        }
+        py.import("builtins")?
+            .call_method1("eval", (self.code.bind(py), self.globals.bind(py), locals))
+    }
+
+    /// Evaluates the cut on the next `n` entries, returning the mask both as
+    /// a numpy array and as plain booleans.
+    fn next_mask<'py>(
+        &mut self,
+        py: Python<'py>,
+        n: usize,
+    ) -> PyResult<(Bound<'py, PyAny>, Vec<bool>)> {
+        let result = self.next_values(py, n)?;
+        let numpy = py.import("numpy")?;
+        let mask = numpy.call_method1("asarray", (result, "bool"))?;
+        let mask = numpy.call_method1("broadcast_to", (mask, n))?;
+        let keep = mask.call_method0("tolist")?.extract::<Vec<bool>>()?;
+        Ok((mask, keep))
+    }
+}
+
+/// Keeps the entries of a batch returned by `ColumnIter::next_batch` where
+/// the cut passed.
+fn apply_mask(
+    py: Python,
+    batch: Py<PyAny>,
+    mask: &Bound<PyAny>,
+    keep: &[bool],
+) -> PyResult<Py<PyAny>> {
+    let batch = batch.bind(py);
+    if let Ok(list) = batch.downcast::<PyList>() {
+        let kept = list
+            .iter()
+            .zip(keep)
+            .filter(|(_, &keep)| keep)
+            .map(|(item, _)| item)
+            .collect::<Vec<_>>();
+        return Ok(PyList::new(py, kept)?.into_any().unbind());
+    }
+    Ok(batch.get_item(mask)?.unbind())
+}
+
+/// Iterator over the entries of a branch of a supported type.
+enum ColumnIter<'a> {
+    Float(Box<dyn Iterator<Item = f32> + 'a>),
+    Double(Box<dyn Iterator<Item = f64> + 'a>),
+    Int8(Box<dyn Iterator<Item = i8> + 'a>),
+    Int16(Box<dyn Iterator<Item = i16> + 'a>),
+    Int32(Box<dyn Iterator<Item = i32> + 'a>),
+    Int64(Box<dyn Iterator<Item = i64> + 'a>),
+    UInt8(Box<dyn Iterator<Item = u8> + 'a>),
+    UInt16(Box<dyn Iterator<Item = u16> + 'a>),
+    UInt32(Box<dyn Iterator<Item = u32> + 'a>),
+    UInt64(Box<dyn Iterator<Item = u64> + 'a>),
+    Bool(Box<dyn Iterator<Item = bool> + 'a>),
+    String(Box<dyn Iterator<Item = String> + 'a>),
+    FloatVec(Box<dyn Iterator<Item = Vec<f32>> + 'a>),
+    DoubleVec(Box<dyn Iterator<Item = Vec<f64>> + 'a>),
+    Int8Vec(Box<dyn Iterator<Item = Vec<i8>> + 'a>),
+    Int16Vec(Box<dyn Iterator<Item = Vec<i16>> + 'a>),
+    Int32Vec(Box<dyn Iterator<Item = Vec<i32>> + 'a>),
+    Int64Vec(Box<dyn Iterator<Item = Vec<i64>> + 'a>),
+    UInt8Vec(Box<dyn Iterator<Item = Vec<u8>> + 'a>),
+    UInt16Vec(Box<dyn Iterator<Item = Vec<u16>> + 'a>),
+    UInt32Vec(Box<dyn Iterator<Item = Vec<u32>> + 'a>),
+    UInt64Vec(Box<dyn Iterator<Item = Vec<u64>> + 'a>),
+    BoolVec(Box<dyn Iterator<Item = Vec<bool>> + 'a>),
+}
+
+impl<'a> ColumnIter<'a> {
+    /// Starts reading `branch` at entry `start`, or returns `None` if the
+    /// branch type is not supported. Fixed-size `T[N]` branches are read like
+    /// vectors of length `N`; `char[N]` ones are read as NUL-terminated strings
+    /// instead if `char_as_string`. ROOT's `Long64_t` and `ULong64_t` spellings
+    /// are read as `int64_t` and `uint64_t`, without oxyroot's type check,
+    /// which only knows the latter.
+    fn new(branch: &'a Branch, start: usize, char_as_string: bool) -> PyResult<Option<Self>> {
+        let iter = match branch.item_type_name().as_str() {
+            "float" => ColumnIter::Float(Box::new(
+                branch
                     .as_iter::<f32>()
                     .map_err(|e| PyValueError::new_err(e.to_string()))?
-                    .collect::<Vec<_>>();
-                Ok(data.into_pyarray(py).into())
-            }
-            "double" => {
-                let data = branch
+                    .skip(start),
+            )),
+            "double" => ColumnIter::Double(Box::new(
+                branch
                     .as_iter::<f64>()
                     .map_err(|e| PyValueError::new_err(e.to_string()))?
-                    .collect::<Vec<_>>();
-                Ok(data.into_pyarray(py).into())
-            }
-            "int32_t" => {
-                let data = branch
+                    .skip(start),
+            )),
+            "int8_t" => ColumnIter::Int8(Box::new(
+                branch
+                    .as_iter::<i8>()
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?
+                    .skip(start),
+            )),
+            "int16_t" => ColumnIter::Int16(Box::new(
+                branch
+                    .as_iter::<i16>()
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?
+                    .skip(start),
+            )),
+            "int32_t" => ColumnIter::Int32(Box::new(
+                branch
                     .as_iter::<i32>()
                     .map_err(|e| PyValueError::new_err(e.to_string()))?
-                    .collect::<Vec<_>>();
-                Ok(data.into_pyarray(py).into())
-            }
-            "int64_t" => {
-                let data = branch
+                    .skip(start),
+            )),
+            "int64_t" => ColumnIter::Int64(Box::new(
+                branch
                     .as_iter::<i64>()
                     .map_err(|e| PyValueError::new_err(e.to_string()))?
-                    .collect::<Vec<_>>();
-                Ok(data.into_pyarray(py).into())
+                    .skip(start),
+            )),
+            "Long64_t" => {
+                ColumnIter::Int64(Box::new(branch.as_iter_unchecked::<i64>().skip(start)))
             }
-            "uint32_t" => {
-                let data = branch
+            "uint8_t" => ColumnIter::UInt8(Box::new(
+                branch
+                    .as_iter::<u8>()
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?
+                    .skip(start),
+            )),
+            "uint16_t" => ColumnIter::UInt16(Box::new(
+                branch
+                    .as_iter::<u16>()
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?
+                    .skip(start),
+            )),
+            "uint32_t" => ColumnIter::UInt32(Box::new(
+                branch
                     .as_iter::<u32>()
                     .map_err(|e| PyValueError::new_err(e.to_string()))?
-                    .collect::<Vec<_>>();
-                Ok(data.into_pyarray(py).into())
-            }
-            "uint64_t" => {
-                let data = branch
+                    .skip(start),
+            )),
+            "uint64_t" => ColumnIter::UInt64(Box::new(
+                branch
                     .as_iter::<u64>()
                     .map_err(|e| PyValueError::new_err(e.to_string()))?
-                    .collect::<Vec<_>>();
-                Ok(data.into_pyarray(py).into())
+                    .skip(start),
+            )),
+            "ULong64_t" => {
+                ColumnIter::UInt64(Box::new(branch.as_iter_unchecked::<u64>().skip(start)))
             }
-            "string" => {
-                let data = branch
+            "bool" => ColumnIter::Bool(Box::new(
+                branch
+                    .as_iter::<bool>()
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?
+                    .skip(start),
+            )),
+            "string" | "char*" | "TString" => ColumnIter::String(Box::new(
+                branch
                     .as_iter::<String>()
                     .map_err(|e| PyValueError::new_err(e.to_string()))?
-                    .collect::<Vec<_>>();
-                Ok(data.into_py_any(py).unwrap())
+                    .skip(start),
+            )),
+            "vector<float>" => ColumnIter::FloatVec(Box::new(
+                branch
+                    .as_iter::<Vec<f32>>()
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?
+                    .skip(start),
+            )),
+            "vector<double>" => ColumnIter::DoubleVec(Box::new(
+                branch
+                    .as_iter::<Vec<f64>>()
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?
+                    .skip(start),
+            )),
+            "vector<int8_t>" => ColumnIter::Int8Vec(Box::new(
+                branch
+                    .as_iter::<Vec<i8>>()
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?
+                    .skip(start),
+            )),
+            "vector<int16_t>" => ColumnIter::Int16Vec(Box::new(
+                branch
+                    .as_iter::<Vec<i16>>()
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?
+                    .skip(start),
+            )),
+            "vector<int32_t>" => ColumnIter::Int32Vec(Box::new(
+                branch
+                    .as_iter::<Vec<i32>>()
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?
+                    .skip(start),
+            )),
+            "vector<int64_t>" => ColumnIter::Int64Vec(Box::new(
+                branch
+                    .as_iter::<Vec<i64>>()
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?
+                    .skip(start),
+            )),
+            "vector<Long64_t>" => {
+                ColumnIter::Int64Vec(Box::new(branch.as_iter_unchecked::<Vec<i64>>().skip(start)))
             }
-            other => Err(PyValueError::new_err(format!(
-                "Unsupported branch type: {}",
-                other
-            ))),
+            "vector<uint8_t>" => ColumnIter::UInt8Vec(Box::new(
+                branch
+                    .as_iter::<Vec<u8>>()
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?
+                    .skip(start),
+            )),
+            "vector<uint16_t>" => ColumnIter::UInt16Vec(Box::new(
+                branch
+                    .as_iter::<Vec<u16>>()
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?
+                    .skip(start),
+            )),
+            "vector<uint32_t>" => ColumnIter::UInt32Vec(Box::new(
+                branch
+                    .as_iter::<Vec<u32>>()
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?
+                    .skip(start),
+            )),
+            "vector<uint64_t>" => ColumnIter::UInt64Vec(Box::new(
+                branch
+                    .as_iter::<Vec<u64>>()
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?
+                    .skip(start),
+            )),
+            "vector<ULong64_t>" => {
+                ColumnIter::UInt64Vec(Box::new(branch.as_iter_unchecked::<Vec<u64>>().skip(start)))
+            }
+            // Bit-packed in memory only: ROOT streams one byte per element.
+            "vector<bool>" => ColumnIter::BoolVec(Box::new(
+                branch
+                    .as_iter::<Vec<bool>>()
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?
+                    .skip(start),
+            )),
+            type_name => match fixed_array(type_name) {
+                Some(("int8_t", n)) if char_as_string => ColumnIter::String(Box::new(
+                    branch
+                        .get_basket(move |r| {
+                            let bytes = (0..n)
+                                .map(|_| read_value(branch, r, 1, |r| r.read_u8()))
+                                .collect::<Vec<_>>();
+                            let end = bytes.iter().position(|&b| b == 0).unwrap_or(n);
+                            String::from_utf8_lossy(&bytes[..end]).into_owned()
+                        })
+                        .skip(start),
+                )),
+                Some(("float", n)) => {
+                    ColumnIter::FloatVec(fixed_array_iter(branch, start, n, |r| r.read_f32()))
+                }
+                Some(("double", n)) => {
+                    ColumnIter::DoubleVec(fixed_array_iter(branch, start, n, |r| r.read_f64()))
+                }
+                Some(("int8_t", n)) => {
+                    ColumnIter::Int8Vec(fixed_array_iter(branch, start, n, |r| r.read_i8()))
+                }
+                Some(("int16_t", n)) => {
+                    ColumnIter::Int16Vec(fixed_array_iter(branch, start, n, |r| r.read_i16()))
+                }
+                Some(("int32_t", n)) => {
+                    ColumnIter::Int32Vec(fixed_array_iter(branch, start, n, |r| r.read_i32()))
+                }
+                Some(("int64_t" | "Long64_t", n)) => {
+                    ColumnIter::Int64Vec(fixed_array_iter(branch, start, n, |r| r.read_i64()))
+                }
+                Some(("uint8_t", n)) => {
+                    ColumnIter::UInt8Vec(fixed_array_iter(branch, start, n, |r| r.read_u8()))
+                }
+                Some(("uint16_t", n)) => {
+                    ColumnIter::UInt16Vec(fixed_array_iter(branch, start, n, |r| r.read_u16()))
+                }
+                Some(("uint32_t", n)) => {
+                    ColumnIter::UInt32Vec(fixed_array_iter(branch, start, n, |r| r.read_u32()))
+                }
+                Some(("uint64_t" | "ULong64_t", n)) => {
+                    ColumnIter::UInt64Vec(fixed_array_iter(branch, start, n, |r| r.read_u64()))
+                }
+                _ => return Ok(None),
+            },
+        };
+        Ok(Some(iter))
+    }
+
+    /// Keeps only every `step`-th entry, starting with the next one.
+    fn step_by(self, step: usize) -> Self {
+        match self {
+            ColumnIter::Float(it) => ColumnIter::Float(Box::new(it.step_by(step))),
+            ColumnIter::Double(it) => ColumnIter::Double(Box::new(it.step_by(step))),
+            ColumnIter::Int8(it) => ColumnIter::Int8(Box::new(it.step_by(step))),
+            ColumnIter::Int16(it) => ColumnIter::Int16(Box::new(it.step_by(step))),
+            ColumnIter::Int32(it) => ColumnIter::Int32(Box::new(it.step_by(step))),
+            ColumnIter::Int64(it) => ColumnIter::Int64(Box::new(it.step_by(step))),
+            ColumnIter::UInt8(it) => ColumnIter::UInt8(Box::new(it.step_by(step))),
+            ColumnIter::UInt16(it) => ColumnIter::UInt16(Box::new(it.step_by(step))),
+            ColumnIter::UInt32(it) => ColumnIter::UInt32(Box::new(it.step_by(step))),
+            ColumnIter::UInt64(it) => ColumnIter::UInt64(Box::new(it.step_by(step))),
+            ColumnIter::Bool(it) => ColumnIter::Bool(Box::new(it.step_by(step))),
+            ColumnIter::String(it) => ColumnIter::String(Box::new(it.step_by(step))),
+            ColumnIter::FloatVec(it) => ColumnIter::FloatVec(Box::new(it.step_by(step))),
+            ColumnIter::DoubleVec(it) => ColumnIter::DoubleVec(Box::new(it.step_by(step))),
+            ColumnIter::Int8Vec(it) => ColumnIter::Int8Vec(Box::new(it.step_by(step))),
+            ColumnIter::Int16Vec(it) => ColumnIter::Int16Vec(Box::new(it.step_by(step))),
+            ColumnIter::Int32Vec(it) => ColumnIter::Int32Vec(Box::new(it.step_by(step))),
+            ColumnIter::Int64Vec(it) => ColumnIter::Int64Vec(Box::new(it.step_by(step))),
+            ColumnIter::UInt8Vec(it) => ColumnIter::UInt8Vec(Box::new(it.step_by(step))),
+            ColumnIter::UInt16Vec(it) => ColumnIter::UInt16Vec(Box::new(it.step_by(step))),
+            ColumnIter::UInt32Vec(it) => ColumnIter::UInt32Vec(Box::new(it.step_by(step))),
+            ColumnIter::UInt64Vec(it) => ColumnIter::UInt64Vec(Box::new(it.step_by(step))),
+            ColumnIter::BoolVec(it) => ColumnIter::BoolVec(Box::new(it.step_by(step))),
         }
     }
 
-    #[getter]
-    fn typename(&self) -> PyResult<String> {
-        let mut file =
-            RootFile::open(&self.path).map_err(|e| PyValueError::new_err(e.to_string()))?;
-        let tree = file
-            .get_tree(&self.tree_name)
-            .map_err(|e| PyValueError::new_err(e.to_string()))?;
-        let branch = tree
-            .branch(&self.name)
-            .ok_or_else(|| PyValueError::new_err("Branch not found"))?;
-        Ok(branch.item_type_name())
+    /// Turns an iterator over fixed-size arrays into one over their `index`-th
+    /// element, or returns `None` for other columns.
+    fn element(self, index: usize) -> Option<Self> {
+        Some(match self {
+            ColumnIter::FloatVec(it) => ColumnIter::Float(Box::new(it.map(move |v| v[index]))),
+            ColumnIter::DoubleVec(it) => ColumnIter::Double(Box::new(it.map(move |v| v[index]))),
+            ColumnIter::Int8Vec(it) => ColumnIter::Int8(Box::new(it.map(move |v| v[index]))),
+            ColumnIter::Int16Vec(it) => ColumnIter::Int16(Box::new(it.map(move |v| v[index]))),
+            ColumnIter::Int32Vec(it) => ColumnIter::Int32(Box::new(it.map(move |v| v[index]))),
+            ColumnIter::Int64Vec(it) => ColumnIter::Int64(Box::new(it.map(move |v| v[index]))),
+            ColumnIter::UInt8Vec(it) => ColumnIter::UInt8(Box::new(it.map(move |v| v[index]))),
+            ColumnIter::UInt16Vec(it) => ColumnIter::UInt16(Box::new(it.map(move |v| v[index]))),
+            ColumnIter::UInt32Vec(it) => ColumnIter::UInt32(Box::new(it.map(move |v| v[index]))),
+            ColumnIter::UInt64Vec(it) => ColumnIter::UInt64(Box::new(it.map(move |v| v[index]))),
+            ColumnIter::BoolVec(it) => ColumnIter::Bool(Box::new(it.map(move |v| v[index]))),
+            _ => return None,
+        })
+    }
+
+    /// Turns a numeric column into one iterator over the values of its next
+    /// `n` entries as `f64`, the values of jagged and fixed-size arrays one
+    /// after the other, or returns `None` for strings and booleans. Read
+    /// errors are raised by `read_error()` once the values are consumed.
+    fn into_f64(self, n: usize) -> Option<Box<dyn Iterator<Item = f64> + 'a>> {
+        Some(match self {
+            ColumnIter::Float(it) => Box::new(it.take(n).map(f64::from)),
+            ColumnIter::Double(it) => Box::new(it.take(n)),
+            ColumnIter::Int8(it) => Box::new(it.take(n).map(f64::from)),
+            ColumnIter::Int16(it) => Box::new(it.take(n).map(f64::from)),
+            ColumnIter::Int32(it) => Box::new(it.take(n).map(f64::from)),
+            ColumnIter::Int64(it) => Box::new(it.take(n).map(|x| x as f64)),
+            ColumnIter::UInt8(it) => Box::new(it.take(n).map(f64::from)),
+            ColumnIter::UInt16(it) => Box::new(it.take(n).map(f64::from)),
+            ColumnIter::UInt32(it) => Box::new(it.take(n).map(f64::from)),
+            ColumnIter::UInt64(it) => Box::new(it.take(n).map(|x| x as f64)),
+            ColumnIter::FloatVec(it) => Box::new(it.take(n).flatten().map(f64::from)),
+            ColumnIter::DoubleVec(it) => Box::new(it.take(n).flatten()),
+            ColumnIter::Int8Vec(it) => Box::new(it.take(n).flatten().map(f64::from)),
+            ColumnIter::Int16Vec(it) => Box::new(it.take(n).flatten().map(f64::from)),
+            ColumnIter::Int32Vec(it) => Box::new(it.take(n).flatten().map(f64::from)),
+            ColumnIter::Int64Vec(it) => Box::new(it.take(n).flatten().map(|x| x as f64)),
+            ColumnIter::UInt8Vec(it) => Box::new(it.take(n).flatten().map(f64::from)),
+            ColumnIter::UInt16Vec(it) => Box::new(it.take(n).flatten().map(f64::from)),
+            ColumnIter::UInt32Vec(it) => Box::new(it.take(n).flatten().map(f64::from)),
+            ColumnIter::UInt64Vec(it) => Box::new(it.take(n).flatten().map(|x| x as f64)),
+            _ => return None,
+        })
+    }
+
+    /// Reads the next `n` entries into a numpy array, or a list for strings and
+    /// a list of numpy arrays for jagged branches.
+    fn next_batch(&mut self, py: Python, n: usize) -> PyResult<Py<PyAny>> {
+        let batch = match self {
+            ColumnIter::Float(it) => collect_n(it, n).into_pyarray(py).into(),
+            ColumnIter::Double(it) => collect_n(it, n).into_pyarray(py).into(),
+            ColumnIter::Int8(it) => collect_n(it, n).into_pyarray(py).into(),
+            ColumnIter::Int16(it) => collect_n(it, n).into_pyarray(py).into(),
+            ColumnIter::Int32(it) => collect_n(it, n).into_pyarray(py).into(),
+            ColumnIter::Int64(it) => collect_n(it, n).into_pyarray(py).into(),
+            ColumnIter::UInt8(it) => collect_n(it, n).into_pyarray(py).into(),
+            ColumnIter::UInt16(it) => collect_n(it, n).into_pyarray(py).into(),
+            ColumnIter::UInt32(it) => collect_n(it, n).into_pyarray(py).into(),
+            ColumnIter::UInt64(it) => collect_n(it, n).into_pyarray(py).into(),
+            ColumnIter::Bool(it) => collect_n(it, n).into_pyarray(py).into(),
+            ColumnIter::String(it) => it.take(n).collect::<Vec<_>>().into_py_any(py)?,
+            ColumnIter::FloatVec(it) => jagged_to_numpy(py, it.take(n))?,
+            ColumnIter::DoubleVec(it) => jagged_to_numpy(py, it.take(n))?,
+            ColumnIter::Int8Vec(it) => jagged_to_numpy(py, it.take(n))?,
+            ColumnIter::Int16Vec(it) => jagged_to_numpy(py, it.take(n))?,
+            ColumnIter::Int32Vec(it) => jagged_to_numpy(py, it.take(n))?,
+            ColumnIter::Int64Vec(it) => jagged_to_numpy(py, it.take(n))?,
+            ColumnIter::UInt8Vec(it) => jagged_to_numpy(py, it.take(n))?,
+            ColumnIter::UInt16Vec(it) => jagged_to_numpy(py, it.take(n))?,
+            ColumnIter::UInt32Vec(it) => jagged_to_numpy(py, it.take(n))?,
+            ColumnIter::UInt64Vec(it) => jagged_to_numpy(py, it.take(n))?,
+            ColumnIter::BoolVec(it) => jagged_to_numpy(py, it.take(n))?,
+        };
+        read_error()?;
+        Ok(batch)
+    }
+
+    /// Reads the next `n` entries of a jagged or fixed-size array branch as
+    /// flat values plus offsets, or returns `None` for other branches.
+    fn next_flat(&mut self, py: Python, n: usize) -> PyResult<Option<(Py<PyAny>, Py<PyAny>)>> {
+        let flat = match self {
+            ColumnIter::FloatVec(it) => flatten_jagged(py, it.take(n)),
+            ColumnIter::DoubleVec(it) => flatten_jagged(py, it.take(n)),
+            ColumnIter::Int8Vec(it) => flatten_jagged(py, it.take(n)),
+            ColumnIter::Int16Vec(it) => flatten_jagged(py, it.take(n)),
+            ColumnIter::Int32Vec(it) => flatten_jagged(py, it.take(n)),
+            ColumnIter::Int64Vec(it) => flatten_jagged(py, it.take(n)),
+            ColumnIter::UInt8Vec(it) => flatten_jagged(py, it.take(n)),
+            ColumnIter::UInt16Vec(it) => flatten_jagged(py, it.take(n)),
+            ColumnIter::UInt32Vec(it) => flatten_jagged(py, it.take(n)),
+            ColumnIter::UInt64Vec(it) => flatten_jagged(py, it.take(n)),
+            ColumnIter::BoolVec(it) => flatten_jagged(py, it.take(n)),
+            _ => return Ok(None),
+        };
+        read_error()?;
+        Ok(Some(flat))
+    }
+
+    /// Reads the next `n` entries into a Python list, of lists for jagged
+    /// branches.
+    fn next_list(&mut self, py: Python, n: usize) -> PyResult<Py<PyAny>> {
+        let list = match self {
+            ColumnIter::Float(it) => to_list(py, it.take(n))?,
+            ColumnIter::Double(it) => to_list(py, it.take(n))?,
+            ColumnIter::Int8(it) => to_list(py, it.take(n))?,
+            ColumnIter::Int16(it) => to_list(py, it.take(n))?,
+            ColumnIter::Int32(it) => to_list(py, it.take(n))?,
+            ColumnIter::Int64(it) => to_list(py, it.take(n))?,
+            ColumnIter::UInt8(it) => to_list(py, it.take(n))?,
+            ColumnIter::UInt16(it) => to_list(py, it.take(n))?,
+            ColumnIter::UInt32(it) => to_list(py, it.take(n))?,
+            ColumnIter::UInt64(it) => to_list(py, it.take(n))?,
+            ColumnIter::Bool(it) => to_list(py, it.take(n))?,
+            ColumnIter::String(it) => to_list(py, it.take(n))?,
+            ColumnIter::FloatVec(it) => jagged_to_list(py, it.take(n))?,
+            ColumnIter::DoubleVec(it) => jagged_to_list(py, it.take(n))?,
+            ColumnIter::Int8Vec(it) => jagged_to_list(py, it.take(n))?,
+            ColumnIter::Int16Vec(it) => jagged_to_list(py, it.take(n))?,
+            ColumnIter::Int32Vec(it) => jagged_to_list(py, it.take(n))?,
+            ColumnIter::Int64Vec(it) => jagged_to_list(py, it.take(n))?,
+            ColumnIter::UInt8Vec(it) => jagged_to_list(py, it.take(n))?,
+            ColumnIter::UInt16Vec(it) => jagged_to_list(py, it.take(n))?,
+            ColumnIter::UInt32Vec(it) => jagged_to_list(py, it.take(n))?,
+            ColumnIter::UInt64Vec(it) => jagged_to_list(py, it.take(n))?,
+            ColumnIter::BoolVec(it) => jagged_to_list(py, it.take(n))?,
+        };
+        read_error()?;
+        Ok(list)
+    }
+
+    /// Reads the next `n` entries into an Arrow array, a list array for jagged
+    /// branches.
+    fn next_arrow(&mut self, n: usize) -> PyResult<ArrayRef> {
+        let array: ArrayRef = match self {
+            ColumnIter::Float(it) => Arc::new(Float32Array::from_iter_values(it.take(n))),
+            ColumnIter::Double(it) => Arc::new(Float64Array::from_iter_values(it.take(n))),
+            ColumnIter::Int8(it) => Arc::new(Int8Array::from_iter_values(it.take(n))),
+            ColumnIter::Int16(it) => Arc::new(Int16Array::from_iter_values(it.take(n))),
+            ColumnIter::Int32(it) => Arc::new(Int32Array::from_iter_values(it.take(n))),
+            ColumnIter::Int64(it) => Arc::new(Int64Array::from_iter_values(it.take(n))),
+            ColumnIter::UInt8(it) => Arc::new(UInt8Array::from_iter_values(it.take(n))),
+            ColumnIter::UInt16(it) => Arc::new(UInt16Array::from_iter_values(it.take(n))),
+            ColumnIter::UInt32(it) => Arc::new(UInt32Array::from_iter_values(it.take(n))),
+            ColumnIter::UInt64(it) => Arc::new(UInt64Array::from_iter_values(it.take(n))),
+            ColumnIter::Bool(it) => Arc::new(BooleanArray::from(it.take(n).collect::<Vec<_>>())),
+            ColumnIter::String(it) => Arc::new(StringArray::from_iter_values(it.take(n))),
+            ColumnIter::FloatVec(it) => jagged_to_arrow::<Float32Type>(it.take(n)),
+            ColumnIter::DoubleVec(it) => jagged_to_arrow::<Float64Type>(it.take(n)),
+            ColumnIter::Int8Vec(it) => jagged_to_arrow::<Int8Type>(it.take(n)),
+            ColumnIter::Int16Vec(it) => jagged_to_arrow::<Int16Type>(it.take(n)),
+            ColumnIter::Int32Vec(it) => jagged_to_arrow::<Int32Type>(it.take(n)),
+            ColumnIter::Int64Vec(it) => jagged_to_arrow::<Int64Type>(it.take(n)),
+            ColumnIter::UInt8Vec(it) => jagged_to_arrow::<UInt8Type>(it.take(n)),
+            ColumnIter::UInt16Vec(it) => jagged_to_arrow::<UInt16Type>(it.take(n)),
+            ColumnIter::UInt32Vec(it) => jagged_to_arrow::<UInt32Type>(it.take(n)),
+            ColumnIter::UInt64Vec(it) => jagged_to_arrow::<UInt64Type>(it.take(n)),
+            ColumnIter::BoolVec(it) => {
+                let mut builder = ListBuilder::new(BooleanBuilder::new());
+                for entry in it.take(n) {
+                    builder.values().append_slice(&entry);
+                    builder.append(true);
+                }
+                Arc::new(builder.finish())
+            }
+        };
+        read_error()?;
+        Ok(array)
+    }
+}
+
+/// Starts a column iterator at entry `start` for each selected branch,
+/// skipping (or, if `strict`, rejecting) missing and unsupported branches.
+fn column_iters<'a>(
+    tree: &'a ReaderTree,
+    columns: Option<Vec<String>>,
+    start: usize,
+    strict: bool,
+    char_as_string: bool,
+    split_fixed_arrays: bool,
+    leaves: &LeafLists,
+) -> PyResult<Vec<(String, ColumnIter<'a>)>> {
+    Ok(branch_column_iters(
+        tree,
+        columns,
+        start,
+        char_as_string,
+        split_fixed_arrays,
+        leaves,
+        &mut |message| skip_branch(strict, message),
+    )?
+    .into_iter()
+    .map(|(_, name, iter)| (name, iter))
+    .collect())
+}
+
+/// Like `column_iters()`, but also gives the branch each column is read
+/// from, which differs from the column name for split fixed-size arrays and
+/// leaf lists. A branch of several leaves, as listed in `leaves`, gives one
+/// column per leaf, named `branch.leaf`. Missing and unsupported branches are
+/// passed to `skip`, which decides whether to warn, raise or only collect
+/// them, the last for threads that can't touch Python.
+fn branch_column_iters<'a>(
+    tree: &'a ReaderTree,
+    columns: Option<Vec<String>>,
+    start: usize,
+    char_as_string: bool,
+    split_fixed_arrays: bool,
+    leaves: &LeafLists,
+    skip: &mut dyn FnMut(String) -> PyResult<()>,
+) -> PyResult<Vec<(String, String, ColumnIter<'a>)>> {
+    let branches_to_read = select_branches(
+        tree.branches().map(|b| b.name().to_string()).collect(),
+        columns,
+    )?;
+
+    let mut iters = Vec::new();
+    for branch_name in branches_to_read {
+        let branch = match tree.branch(&branch_name) {
+            Some(branch) => branch,
+            None => {
+                skip(format!("Branch '{}' not found", branch_name))?;
+                continue;
+            }
+        };
+        if let Some(message) = entries_mismatch(tree, branch) {
+            skip(message)?;
+            continue;
+        }
+        if let Some(branch_leaves) = leaves.get(&branch_name) {
+            for (index, leaf) in branch_leaves.iter().enumerate() {
+                iters.push((
+                    branch_name.clone(),
+                    format!("{}.{}", branch_name, leaf.name),
+                    leaf_iter(branch, start, branch_leaves, index),
+                ));
+            }
+            continue;
+        }
+        match fixed_array(&branch.item_type_name()) {
+            // Each element re-reads the branch, which is cheap for the small
+            // arrays (positions, momenta) this is meant for.
+            Some((element, n))
+                if split_fixed_arrays && !(char_as_string && element == "int8_t") =>
+            {
+                for index in 0..n {
+                    match ColumnIter::new(branch, start, false)?
+                        .and_then(|iter| iter.element(index))
+                    {
+                        Some(iter) => iters.push((
+                            branch_name.clone(),
+                            format!("{}_{}", branch_name, index),
+                            iter,
+                        )),
+                        None => {
+                            skip(unsupported_branch(branch))?;
+                            break;
+                        }
+                    }
+                }
+            }
+            _ => match ColumnIter::new(branch, start, char_as_string)? {
+                Some(iter) => iters.push((branch_name.clone(), branch_name, iter)),
+                None => skip(unsupported_branch(branch))?,
+            },
+        }
+    }
+    Ok(iters)
+}
+
+/// Splits a `columns` argument across a tree and its friends. Each tree gets
+/// the names and pattern matches among its branches not already taken by an
+/// earlier tree; names found nowhere stay with the first tree, which then
+/// reports them as missing.
+fn split_columns(
+    trees: &[&ReaderTree],
+    columns: Option<Vec<String>>,
+) -> PyResult<Vec<Option<Vec<String>>>> {
+    if trees.len() == 1 {
+        return Ok(vec![columns]);
+    }
+    let mut taken: Vec<String> = Vec::new();
+    let mut branches = Vec::new();
+    for tree in trees {
+        let own = tree
+            .branches()
+            .map(|b| b.name().to_string())
+            .filter(|name| !taken.contains(name))
+            .collect::<Vec<_>>();
+        taken.extend(own.iter().cloned());
+        branches.push(own);
+    }
+    let Some(columns) = columns else {
+        return Ok(branches.into_iter().map(Some).collect());
+    };
+
+    let mut split = vec![Vec::new(); trees.len()];
+    for column in columns {
+        let mut found = false;
+        for (own, selected) in branches.iter().zip(split.iter_mut()) {
+            for name in select_branches(own.clone(), Some(vec![column.clone()]))? {
+                if own.contains(&name) {
+                    selected.push(name);
+                    found = true;
+                }
+            }
+        }
+        if !found {
+            split[0].push(column);
+        }
+    }
+    Ok(split.into_iter().map(Some).collect())
+}
+
+/// Renames the columns listed in `aliases` to their alias.
+fn rename_columns(batch: RecordBatch, aliases: &HashMap<String, String>) -> PyResult<RecordBatch> {
+    let fields = batch
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| match aliases.get(field.name()) {
+            Some(alias) => field.as_ref().clone().with_name(alias),
+            None => field.as_ref().clone(),
+        })
+        .collect::<Vec<_>>();
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), batch.columns().to_vec())
+        .map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Casts the columns listed in `dtypes`, element-wise for list columns.
+/// Values that don't fit the new type raise instead of becoming nulls.
+fn cast_columns(batch: RecordBatch, dtypes: &HashMap<String, String>) -> PyResult<RecordBatch> {
+    let mut fields = Vec::new();
+    let mut columns = Vec::new();
+    for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+        let Some(dtype) = dtypes.get(field.name()) else {
+            fields.push(field.as_ref().clone());
+            columns.push(column.clone());
+            continue;
+        };
+        let target = arrow_type(dtype).ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "Unknown dtype '{}' for branch '{}'",
+                dtype,
+                field.name()
+            ))
+        })?;
+        let target = match column.data_type() {
+            DataType::List(item) => {
+                DataType::List(Arc::new(item.as_ref().clone().with_data_type(target)))
+            }
+            _ => target,
+        };
+        let options = CastOptions {
+            safe: false,
+            ..Default::default()
+        };
+        columns.push(cast_with_options(column, &target, &options).map_err(|e| {
+            PyValueError::new_err(format!(
+                "Cannot cast branch '{}' to {}: {}",
+                field.name(),
+                dtype,
+                e
+            ))
+        })?);
+        fields.push(field.as_ref().clone().with_data_type(target));
+    }
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+        .map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Dictionary-encodes the string columns, so each distinct value is stored
+/// once per batch.
+fn dictionary_strings(batch: RecordBatch) -> PyResult<RecordBatch> {
+    let target = DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+    let mut fields = Vec::new();
+    let mut columns = Vec::new();
+    for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+        if column.data_type() != &DataType::Utf8 {
+            fields.push(field.as_ref().clone());
+            columns.push(column.clone());
+            continue;
+        }
+        columns.push(cast(column, &target).map_err(|e| PyValueError::new_err(e.to_string()))?);
+        fields.push(field.as_ref().clone().with_data_type(target.clone()));
+    }
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+        .map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Casts a column returned by `ColumnIter::next_batch` to `dtype` with numpy,
+/// element-wise for jagged branches.
+fn cast_numpy(py: Python, column: Py<PyAny>, name: &str, dtype: &str) -> PyResult<Py<PyAny>> {
+    if arrow_type(dtype).is_none() {
+        return Err(PyValueError::new_err(format!(
+            "Unknown dtype '{}' for branch '{}'",
+            dtype, name
+        )));
+    }
+    let to_err = |e: PyErr| {
+        PyValueError::new_err(format!(
+            "Cannot cast branch '{}' to {}: {}",
+            name,
+            dtype,
+            e.value(py)
+        ))
+    };
+    let column = column.bind(py);
+    if !column.is_instance_of::<PyList>() {
+        return Ok(cast_array(py, column, dtype).map_err(to_err)?.unbind());
+    }
+    let entries = column
+        .try_iter()?
+        .map(|entry| cast_array(py, &entry?, dtype))
+        .collect::<PyResult<Vec<_>>>()
+        .map_err(to_err)?;
+    Ok(PyList::new(py, entries)?.into_any().unbind())
+}
+
+/// `array.astype(dtype)`, raising like `cast_columns` does when an integer
+/// `dtype` can't hold a value instead of letting numpy wrap it.
+fn cast_array<'py>(
+    py: Python<'py>,
+    array: &Bound<'py, PyAny>,
+    dtype: &str,
+) -> PyResult<Bound<'py, PyAny>> {
+    let numpy = py.import("numpy")?;
+    let target = numpy.call_method1("dtype", (dtype,))?;
+    let info = match target.getattr("kind")?.extract::<String>()?.as_str() {
+        "i" | "u" => numpy.call_method1("iinfo", (&target,))?,
+        _ => return array.call_method1("astype", (dtype,)),
+    };
+    let above = numpy.call_method1("greater_equal", (array, info.getattr("min")?))?;
+    let below = numpy.call_method1("less_equal", (array, info.getattr("max")?))?;
+    let fits = numpy
+        .call_method1("logical_and", (above, below))?
+        .call_method0("all")?
+        .extract::<bool>()?;
+    if !fits {
+        return Err(PyValueError::new_err(format!(
+            "values out of range for {}",
+            dtype
+        )));
+    }
+    array.call_method1("astype", (dtype,))
+}
+
+/// The arrow type for a dtype name as reported by `schema()`.
+fn arrow_type(dtype: &str) -> Option<DataType> {
+    Some(match dtype {
+        "float32" => DataType::Float32,
+        "float64" => DataType::Float64,
+        "int8" => DataType::Int8,
+        "int16" => DataType::Int16,
+        "int32" => DataType::Int32,
+        "int64" => DataType::Int64,
+        "uint8" => DataType::UInt8,
+        "uint16" => DataType::UInt16,
+        "uint32" => DataType::UInt32,
+        "uint64" => DataType::UInt64,
+        "bool" => DataType::Boolean,
+        "str" => DataType::Utf8,
+        _ => return None,
+    })
+}
+
+/// Reads the next `n` entries of every column into a record batch.
+fn next_record_batch(
+    iters: &mut [(String, ColumnIter)],
+    n: usize,
+    nullable: bool,
+) -> PyResult<RecordBatch> {
+    let columns = iters
+        .iter_mut()
+        .map(|(branch_name, iter)| Ok((branch_name.clone(), iter.next_arrow(n)?)))
+        .collect::<PyResult<_>>()?;
+    to_record_batch(columns, nullable)
+}
+
+fn to_record_batch(columns: NamedArrays, nullable: bool) -> PyResult<RecordBatch> {
+    let mut fields = Vec::new();
+    let mut arrays = Vec::new();
+    for (name, array) in columns {
+        fields.push(Field::new(name, array.data_type().clone(), nullable));
+        arrays.push(array);
+    }
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)
+        .map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+type NamedArrays = Vec<(String, ArrayRef)>;
+/// One file's share of a branch in `Chain.arrays()`: its entry count and
+/// named columns, or `None` where `fill_value` stands in.
+type ChainPart = (usize, Option<Vec<(String, Py<PyAny>)>>);
+type BatchReceiver = mpsc::Receiver<PyResult<NamedArrays>>;
+
+/// Where `to_parquet()` gets its record batches from: iterators on this
+/// thread, or worker threads that each read some of the branches from their
+/// own copy of the tree, as oxyroot trees can't be shared across threads.
+enum BatchSource<'a> {
+    Serial(Vec<(String, ColumnIter<'a>)>),
+    Parallel {
+        /// Column names in schema order.
+        names: Vec<String>,
+        /// Where to ask each worker for its next `n` entries and get them.
+        workers: Vec<(mpsc::Sender<usize>, Mutex<BatchReceiver>)>,
+        /// Set on drop, so workers stop between branches instead of
+        /// finishing a batch nobody will read.
+        cancelled: Arc<AtomicBool>,
+    },
+}
+
+impl<'a> BatchSource<'a> {
+    /// Reads `iters` on up to `threads` threads, splitting their branches
+    /// between them, at least `PARALLEL_MIN_BRANCHES_PER_THREAD` each. Workers
+    /// open `path` themselves and must not touch Python.
+    fn new<'scope>(
+        scope: &'scope std::thread::Scope<'scope, '_>,
+        iters: Vec<(String, String, ColumnIter<'a>)>,
+        threads: usize,
+        path: String,
+        tree_name: &str,
+        start: usize,
+        split_fixed_arrays: bool,
+    ) -> Self {
+        let mut branches: Vec<String> = Vec::new();
+        for (branch, _, _) in &iters {
+            if !branches.contains(branch) {
+                branches.push(branch.clone());
+            }
+        }
+        let threads = threads.min(branches.len() / PARALLEL_MIN_BRANCHES_PER_THREAD);
+        if threads <= 1 {
+            return BatchSource::Serial(
+                iters
+                    .into_iter()
+                    .map(|(_, name, iter)| (name, iter))
+                    .collect(),
+            );
+        }
+
+        let names = iters.into_iter().map(|(_, name, _)| name).collect();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let workers = (0..threads)
+            .map(|worker| {
+                let branches = branches
+                    .iter()
+                    .skip(worker)
+                    .step_by(threads)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let (path, tree_name) = (path.clone(), tree_name.to_string());
+                let cancelled = cancelled.clone();
+                let (request, requests) = mpsc::channel::<usize>();
+                let (result, results) = mpsc::channel();
+                scope.spawn(move || {
+                    // These branches were already checked, so `strict` only
+                    // guards against skipping one silently.
+                    let file = FileHandle::for_worker(path);
+                    let (tree, leaves) = match file
+                        .tree(&tree_name)
+                        .and_then(|tree| file.leaf_lists(&tree_name).map(|leaves| (tree, leaves)))
+                    {
+                        Ok(tree) => tree,
+                        Err(e) => return result.send(Err(e)).unwrap_or(()),
+                    };
+                    let mut iters = match column_iters(
+                        &tree,
+                        Some(branches),
+                        start,
+                        true,
+                        false,
+                        split_fixed_arrays,
+                        &leaves,
+                    ) {
+                        Ok(iters) => iters,
+                        Err(e) => return result.send(Err(e)).unwrap_or(()),
+                    };
+                    for n in requests {
+                        let mut columns = Vec::new();
+                        for (name, iter) in iters.iter_mut() {
+                            if cancelled.load(Ordering::Relaxed) {
+                                return;
+                            }
+                            match iter.next_arrow(n) {
+                                Ok(array) => columns.push((name.clone(), array)),
+                                Err(e) => return result.send(Err(e)).unwrap_or(()),
+                            }
+                        }
+                        if result.send(Ok(columns)).is_err() {
+                            return;
+                        }
+                    }
+                });
+                (request, Mutex::new(results))
+            })
+            .collect();
+        BatchSource::Parallel {
+            names,
+            workers,
+            cancelled,
+        }
+    }
+
+    /// The next `n` entries. Waiting for workers releases the GIL and stops
+    /// on Ctrl-C.
+    fn next_batch(&mut self, py: Python, n: usize, nullable: bool) -> PyResult<RecordBatch> {
+        let (names, workers) = match self {
+            BatchSource::Serial(iters) => return next_record_batch(iters, n, nullable),
+            BatchSource::Parallel { names, workers, .. } => (names, workers),
+        };
+        let worker_failed = || PyValueError::new_err("A parquet reader thread failed");
+        for (request, _) in workers.iter() {
+            request.send(n).map_err(|_| worker_failed())?;
+        }
+        let mut columns = HashMap::new();
+        for (_, results) in workers.iter() {
+            columns.extend(recv_interruptible(py, results)?.ok_or_else(worker_failed)??);
+        }
+        let columns = names
+            .iter()
+            .map(|name| {
+                let array = columns.remove(name).ok_or_else(worker_failed)?;
+                Ok((name.clone(), array))
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        to_record_batch(columns, nullable)
+    }
+}
+
+impl Drop for BatchSource<'_> {
+    fn drop(&mut self) {
+        if let BatchSource::Parallel { cancelled, .. } = self {
+            cancelled.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Waits for a message from worker threads with the GIL released, checking
+/// for Ctrl-C in between. `None` means every sender is gone.
+fn recv_interruptible<T: Send>(
+    py: Python,
+    receiver: &Mutex<mpsc::Receiver<T>>,
+) -> PyResult<Option<T>> {
+    loop {
+        py.check_signals()?;
+        let received = py.detach(|| {
+            receiver
+                .lock()
+                .unwrap()
+                .recv_timeout(Duration::from_millis(100))
+        });
+        match received {
+            Ok(message) => return Ok(Some(message)),
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => return Ok(None),
+        }
+    }
+}
+
+/// Replaces the values of numeric columns equal to one of `missing_values`
+/// with nulls. The columns must be nullable.
+fn null_missing(batch: RecordBatch, missing_values: &[f64]) -> PyResult<RecordBatch> {
+    let mut columns = Vec::new();
+    for column in batch.columns() {
+        if !column.data_type().is_numeric() {
+            columns.push(column.clone());
+            continue;
+        }
+        let values =
+            cast(column, &DataType::Float64).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let missing = values
+            .as_primitive::<Float64Type>()
+            .iter()
+            .map(|value| Some(value.is_some_and(|value| missing_values.contains(&value))))
+            .collect::<BooleanArray>();
+        columns.push(nullif(column, &missing).map_err(|e| PyValueError::new_err(e.to_string()))?);
+    }
+    RecordBatch::try_new(batch.schema(), columns).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Wraps a numeric numpy array in a `numpy.ma.MaskedArray` hiding the entries
+/// equal to one of `missing_values`. Other columns are returned unchanged.
+fn mask_missing(py: Python, column: Py<PyAny>, missing_values: &[f64]) -> PyResult<Py<PyAny>> {
+    let array = column.bind(py);
+    if array.is_instance_of::<PyList>() {
+        return Ok(column);
+    }
+    let kind: String = array.getattr("dtype")?.getattr("kind")?.extract()?;
+    if !matches!(kind.as_str(), "f" | "i" | "u") {
+        return Ok(column);
+    }
+    let numpy = py.import("numpy")?;
+    let mask = numpy.call_method1("isin", (array, missing_values.to_vec()))?;
+    Ok(numpy
+        .getattr("ma")?
+        .call_method1("masked_array", (array, mask))?
+        .unbind())
+}
+
+/// Whether a column has entries and all of them are `None`, or masked in a
+/// `numpy.ma.MaskedArray`.
+fn is_all_null(py: Python, column: &Bound<PyAny>) -> PyResult<bool> {
+    if let Ok(list) = column.downcast::<PyList>() {
+        return Ok(!list.is_empty() && list.iter().all(|item| item.is_none()));
+    }
+    let ma = py.import("numpy")?.getattr("ma")?;
+    if !column.is_instance(&ma.getattr("MaskedArray")?)? {
+        return Ok(false);
+    }
+    let size: usize = column.getattr("size")?.extract()?;
+    Ok(size > 0
+        && ma
+            .call_method1("getmaskarray", (column,))?
+            .call_method0("all")?
+            .is_truthy()?)
+}
+
+/// Replaces the values of an integer column with their labels, giving a list
+/// of strings with `None` for values that have no label.
+fn label_enum(
+    py: Python,
+    column: Py<PyAny>,
+    name: &str,
+    labels: &HashMap<i64, String>,
+) -> PyResult<Py<PyAny>> {
+    let array = column.bind(py);
+    let kind = if array.is_instance_of::<PyList>() {
+        None
+    } else {
+        Some(
+            array
+                .getattr("dtype")?
+                .getattr("kind")?
+                .extract::<String>()?,
+        )
+    };
+    if !matches!(kind.as_deref(), Some("i" | "u")) {
+        return Err(PyValueError::new_err(format!(
+            "enum_maps only applies to integer branches, not '{}'",
+            name
+        )));
+    }
+    let values = array.call_method0("tolist")?.extract::<Vec<i64>>()?;
+    Ok(
+        PyList::new(py, values.iter().map(|value| labels.get(value)))?
+            .into_any()
+            .unbind(),
+    )
+}
+
+fn jagged_to_numpy<T: Element>(
+    py: Python,
+    entries: impl Iterator<Item = Vec<T>>,
+) -> PyResult<Py<PyAny>> {
+    entries
+        .map(|entry| entry.into_pyarray(py).into_any().unbind())
+        .collect::<Vec<_>>()
+        .into_py_any(py)
+}
+
+/// Collects up to `n` items into a `Vec` allocated for all of them up front;
+/// branch iterators don't know their length, so `collect()` would keep
+/// reallocating. `into_pyarray` then hands the buffer to numpy without a copy.
+fn collect_n<T>(it: impl Iterator<Item = T>, n: usize) -> Vec<T> {
+    let mut values = Vec::with_capacity(n);
+    values.extend(it.take(n));
+    values
+}
+
+/// Concatenates `entries` into one array, returned with the `int64` offsets
+/// where each entry starts plus a final one at the end, as Awkward lays
+/// out lists.
+fn flatten_jagged<T: Element>(
+    py: Python,
+    entries: impl Iterator<Item = Vec<T>>,
+) -> (Py<PyAny>, Py<PyAny>) {
+    let mut values = Vec::new();
+    let mut offsets = vec![0i64];
+    for entry in entries {
+        values.extend(entry);
+        offsets.push(values.len() as i64);
     }
+    (
+        values.into_pyarray(py).into_any().unbind(),
+        offsets.into_pyarray(py).into_any().unbind(),
+    )
+}
+
+/// Builds a Python list item by item, so `u8` values stay ints rather than
+/// becoming `bytes`.
+fn to_list<T: for<'py> IntoPyObject<'py>>(
+    py: Python,
+    items: impl Iterator<Item = T>,
+) -> PyResult<Py<PyAny>> {
+    Ok(PyList::new(py, items.collect::<Vec<_>>())?
+        .into_any()
+        .unbind())
+}
+
+fn jagged_to_list<T: for<'py> IntoPyObject<'py>>(
+    py: Python,
+    entries: impl Iterator<Item = Vec<T>>,
+) -> PyResult<Py<PyAny>> {
+    let lists = entries
+        .map(|entry| to_list(py, entry.into_iter()))
+        .collect::<PyResult<Vec<_>>>()?;
+    to_list(py, lists.into_iter())
+}
+
+/// Builds an Arrow list array with one list per entry of a jagged branch.
+fn jagged_to_arrow<T: ArrowPrimitiveType>(
+    entries: impl Iterator<Item = Vec<T::Native>>,
+) -> ArrayRef {
+    Arc::new(ListArray::from_iter_primitive::<T, _, _>(
+        entries.map(|entry| Some(entry.into_iter().map(Some))),
+    ))
+}
+
+#[pyclass(unsendable)]
+struct PyBatchIterator {
+    batch_size: usize,
+    remaining: usize,
+    // Declared before `_tree` so the branch iterators are dropped first.
+    iters: Vec<(String, ColumnIter<'static>)>,
+    _tree: Rc<ReaderTree>,
+}
+
+impl PyBatchIterator {
+    fn new(
+        tree: Rc<ReaderTree>,
+        leaves: &LeafLists,
+        batch_size: usize,
+        columns: Option<Vec<String>>,
+        strict: bool,
+    ) -> PyResult<Self> {
+        // SAFETY: the tree lives inside the `Rc<ReaderTree>` stored in the
+        // iterator below, which is dropped after `iters` and never moves.
+        let tree_ref: &'static ReaderTree = unsafe { &*Rc::as_ptr(&tree) };
+        let selected = select_branches(
+            tree.branches().map(|b| b.name().to_string()).collect(),
+            columns.clone(),
+        )?;
+        let batch_entries = batch_size.min(tree.entries() as usize);
+        check_memory_limit(estimate_size(&tree, leaves, &selected, batch_entries))?;
+        let iters = column_iters(tree_ref, columns, 0, strict, false, false, leaves)?;
+
+        Ok(PyBatchIterator {
+            batch_size,
+            remaining: tree.entries() as usize,
+            iters,
+            _tree: tree,
+        })
+    }
+}
+
+#[pymethods]
+impl PyBatchIterator {
+    fn __iter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python) -> PyResult<Option<Py<PyDict>>> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        let n = self.batch_size.min(self.remaining);
+        self.remaining -= n;
+
+        let batch = PyDict::new(py);
+        for (name, iter) in self.iters.iter_mut() {
+            batch.set_item(name.as_str(), iter.next_batch(py, n)?)?;
+        }
+        Ok(Some(batch.into()))
+    }
+}
+
+/// Batches of a `Chain`, read one file at a time. Batches don't span files,
+/// so the last one of each file may be shorter.
+#[pyclass(unsendable)]
+struct PyChainBatchIterator {
+    name: String,
+    batch_size: usize,
+    columns: Option<Vec<String>>,
+    strict: bool,
+    promote_ints: bool,
+    files: std::vec::IntoIter<Rc<FileHandle>>,
+    current: Option<PyBatchIterator>,
+}
+
+#[pymethods]
+impl PyChainBatchIterator {
+    fn __iter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python) -> PyResult<Option<Py<PyDict>>> {
+        loop {
+            if let Some(current) = self.current.as_mut() {
+                if let Some(batch) = current.__next__(py)? {
+                    if self.promote_ints {
+                        let batch = batch.bind(py);
+                        for (name, column) in batch.iter() {
+                            batch.set_item(name, promote_int(py, column.unbind())?)?;
+                        }
+                    }
+                    return Ok(Some(batch));
+                }
+                self.current = None;
+            }
+            let Some(file) = self.files.next() else {
+                return Ok(None);
+            };
+            match file
+                .tree(&self.name)
+                .and_then(|tree| file.leaf_lists(&self.name).map(|leaves| (tree, leaves)))
+            {
+                Ok((tree, leaves)) => {
+                    self.current = Some(PyBatchIterator::new(
+                        tree,
+                        &leaves,
+                        self.batch_size,
+                        self.columns.clone(),
+                        self.strict,
+                    )?)
+                }
+                Err(e) => skip_branch(
+                    self.strict,
+                    format!(
+                        "Cannot read '{}' from '{}': {}",
+                        self.name,
+                        file.path,
+                        e.value(py)
+                    ),
+                )?,
+            }
+        }
+    }
+}
+
+/// Reads the selected branches of a tree one at a time, yielding
+/// `(name, array)` pairs.
+#[pyclass(unsendable)]
+struct PyArrayIterator {
+    branches: std::vec::IntoIter<String>,
+    strict: bool,
+    leaves: Rc<LeafLists>,
+    /// Leaf columns of the last leaf-list branch still to yield, last first.
+    pending: Vec<(String, Py<PyAny>)>,
+    tree: Rc<ReaderTree>,
+}
+
+#[pymethods]
+impl PyArrayIterator {
+    fn __iter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python) -> PyResult<Option<(String, Py<PyAny>)>> {
+        if let Some(column) = self.pending.pop() {
+            return Ok(Some(column));
+        }
+        for branch_name in self.branches.by_ref() {
+            let branch = match self.tree.branch(&branch_name) {
+                Some(branch) => branch,
+                None => {
+                    skip_branch(self.strict, format!("Branch '{}' not found", branch_name))?;
+                    continue;
+                }
+            };
+            let entries = self.tree.entries() as usize;
+            check_memory_limit(estimate_size(
+                &self.tree,
+                &self.leaves,
+                std::slice::from_ref(&branch_name),
+                entries,
+            ))?;
+            if let Some(leaves) = self.leaves.get(&branch_name) {
+                for (index, leaf) in leaves.iter().enumerate().rev() {
+                    let array = leaf_iter(branch, 0, leaves, index).next_batch(py, entries)?;
+                    self.pending
+                        .push((format!("{}.{}", branch_name, leaf.name), array));
+                }
+                return Ok(self.pending.pop());
+            }
+            match branch_to_numpy(py, branch, 0..entries, false)? {
+                Some(array) => return Ok(Some((branch_name, array))),
+                None => skip_branch(self.strict, unsupported_branch(branch))?,
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[pymethods]
+impl PyBranch {
+    /// Reads the branch into a numpy array. `step` reads only every
+    /// `step`-th entry from `entry_start` on. With `missing_values`, a numeric
+    /// branch is returned as a `numpy.ma.MaskedArray` hiding those values, as
+    /// in `Tree.arrays()`.
+    #[pyo3(signature = (entry_start = None, entry_stop = None, char_as_string = false, step = 1, missing_values = None))]
+    fn array(
+        &self,
+        py: Python,
+        entry_start: Option<usize>,
+        entry_stop: Option<usize>,
+        char_as_string: bool,
+        step: usize,
+        missing_values: Option<Vec<f64>>,
+    ) -> PyResult<Py<PyAny>> {
+        if step == 0 {
+            return Err(PyValueError::new_err("step must be greater than 0"));
+        }
+        let tree = self.file.tree(&self.tree_name)?;
+        let branch = tree
+            .branch(&self.name)
+            .ok_or_else(|| PyValueError::new_err("Branch not found"))?;
+        let range = entry_range(tree.entries() as usize, entry_start, entry_stop)?;
+
+        let iter = ColumnIter::new(branch, range.start, char_as_string)?.ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "Unsupported branch type: {}",
+                branch.item_type_name()
+            ))
+        })?;
+        let array = iter
+            .step_by(step)
+            .next_batch(py, range.len().div_ceil(step))?;
+        match &missing_values {
+            Some(missing_values) => mask_missing(py, array, missing_values),
+            None => Ok(array),
+        }
+    }
+
+    /// Like `array()`, but raises unless the branch holds `float32` scalars,
+    /// so a type change between productions fails instead of passing
+    /// through. The other `array_*` methods do the same for their dtype.
+    #[pyo3(signature = (entry_start = None, entry_stop = None))]
+    fn array_f32(
+        &self,
+        py: Python,
+        entry_start: Option<usize>,
+        entry_stop: Option<usize>,
+    ) -> PyResult<Py<PyAny>> {
+        self.array_as(py, "float32", entry_start, entry_stop)
+    }
+
+    #[pyo3(signature = (entry_start = None, entry_stop = None))]
+    fn array_f64(
+        &self,
+        py: Python,
+        entry_start: Option<usize>,
+        entry_stop: Option<usize>,
+    ) -> PyResult<Py<PyAny>> {
+        self.array_as(py, "float64", entry_start, entry_stop)
+    }
+
+    #[pyo3(signature = (entry_start = None, entry_stop = None))]
+    fn array_i8(
+        &self,
+        py: Python,
+        entry_start: Option<usize>,
+        entry_stop: Option<usize>,
+    ) -> PyResult<Py<PyAny>> {
+        self.array_as(py, "int8", entry_start, entry_stop)
+    }
+
+    #[pyo3(signature = (entry_start = None, entry_stop = None))]
+    fn array_i16(
+        &self,
+        py: Python,
+        entry_start: Option<usize>,
+        entry_stop: Option<usize>,
+    ) -> PyResult<Py<PyAny>> {
+        self.array_as(py, "int16", entry_start, entry_stop)
+    }
+
+    #[pyo3(signature = (entry_start = None, entry_stop = None))]
+    fn array_i32(
+        &self,
+        py: Python,
+        entry_start: Option<usize>,
+        entry_stop: Option<usize>,
+    ) -> PyResult<Py<PyAny>> {
+        self.array_as(py, "int32", entry_start, entry_stop)
+    }
+
+    #[pyo3(signature = (entry_start = None, entry_stop = None))]
+    fn array_i64(
+        &self,
+        py: Python,
+        entry_start: Option<usize>,
+        entry_stop: Option<usize>,
+    ) -> PyResult<Py<PyAny>> {
+        self.array_as(py, "int64", entry_start, entry_stop)
+    }
+
+    #[pyo3(signature = (entry_start = None, entry_stop = None))]
+    fn array_u8(
+        &self,
+        py: Python,
+        entry_start: Option<usize>,
+        entry_stop: Option<usize>,
+    ) -> PyResult<Py<PyAny>> {
+        self.array_as(py, "uint8", entry_start, entry_stop)
+    }
+
+    #[pyo3(signature = (entry_start = None, entry_stop = None))]
+    fn array_u16(
+        &self,
+        py: Python,
+        entry_start: Option<usize>,
+        entry_stop: Option<usize>,
+    ) -> PyResult<Py<PyAny>> {
+        self.array_as(py, "uint16", entry_start, entry_stop)
+    }
+
+    #[pyo3(signature = (entry_start = None, entry_stop = None))]
+    fn array_u32(
+        &self,
+        py: Python,
+        entry_start: Option<usize>,
+        entry_stop: Option<usize>,
+    ) -> PyResult<Py<PyAny>> {
+        self.array_as(py, "uint32", entry_start, entry_stop)
+    }
+
+    #[pyo3(signature = (entry_start = None, entry_stop = None))]
+    fn array_u64(
+        &self,
+        py: Python,
+        entry_start: Option<usize>,
+        entry_stop: Option<usize>,
+    ) -> PyResult<Py<PyAny>> {
+        self.array_as(py, "uint64", entry_start, entry_stop)
+    }
+
+    /// Reads a jagged or fixed-size array branch as `(values, offsets)`: all
+    /// values in one flat array, and the `int64` offsets where each entry's
+    /// values start, with a final offset at the end. Entry `i` is
+    /// `values[offsets[i]:offsets[i + 1]]`.
+    #[pyo3(signature = (entry_start = None, entry_stop = None))]
+    fn array_with_offsets(
+        &self,
+        py: Python,
+        entry_start: Option<usize>,
+        entry_stop: Option<usize>,
+    ) -> PyResult<(Py<PyAny>, Py<PyAny>)> {
+        let tree = self.file.tree(&self.tree_name)?;
+        let branch = tree
+            .branch(&self.name)
+            .ok_or_else(|| PyValueError::new_err("Branch not found"))?;
+        let range = entry_range(tree.entries() as usize, entry_start, entry_stop)?;
+
+        let flat = match ColumnIter::new(branch, range.start, false)? {
+            Some(mut iter) => iter.next_flat(py, range.len())?,
+            None => None,
+        };
+        flat.ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "Branch '{}' has type {}, which is not a numeric array",
+                self.name,
+                branch.item_type_name()
+            ))
+        })
+    }
+
+    /// Summary statistics of a numeric branch, computed while streaming it
+    /// rather than loading it into an array: `min`, `max` and `mean` (`None`
+    /// with no values), `count` of values and `null_count` of NaNs, which are
+    /// left out of the others. Jagged and fixed-size arrays count every
+    /// element. 64-bit integers are compared as `f64`.
+    #[pyo3(signature = (entry_start = None, entry_stop = None))]
+    fn stats<'py>(
+        &self,
+        py: Python<'py>,
+        entry_start: Option<usize>,
+        entry_stop: Option<usize>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let tree = self.file.tree(&self.tree_name)?;
+        let branch = tree
+            .branch(&self.name)
+            .ok_or_else(|| PyValueError::new_err("Branch not found"))?;
+        let range = entry_range(tree.entries() as usize, entry_start, entry_stop)?;
+
+        let values = ColumnIter::new(branch, range.start, false)?
+            .and_then(|iter| iter.into_f64(range.len()));
+        let values = values.ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "Branch '{}' has type {}, which is not numeric",
+                self.name,
+                branch.item_type_name()
+            ))
+        })?;
+
+        let (mut count, mut null_count, mut sum) = (0usize, 0usize, 0f64);
+        let (mut min, mut max) = (f64::INFINITY, f64::NEG_INFINITY);
+        for x in values {
+            if x.is_nan() {
+                null_count += 1;
+                continue;
+            }
+            count += 1;
+            sum += x;
+            min = min.min(x);
+            max = max.max(x);
+        }
+        read_error()?;
+
+        let stats = PyDict::new(py);
+        let some = |x: f64| (count > 0).then_some(x);
+        stats.set_item("min", some(min))?;
+        stats.set_item("max", some(max))?;
+        stats.set_item("mean", some(sum / count as f64))?;
+        stats.set_item("count", count)?;
+        stats.set_item("null_count", null_count)?;
+        Ok(stats)
+    }
+
+    /// Like `array()`, but always returns a plain Python list.
+    #[pyo3(signature = (entry_start = None, entry_stop = None, char_as_string = false))]
+    fn tolist(
+        &self,
+        py: Python,
+        entry_start: Option<usize>,
+        entry_stop: Option<usize>,
+        char_as_string: bool,
+    ) -> PyResult<Py<PyAny>> {
+        let tree = self.file.tree(&self.tree_name)?;
+        let branch = tree
+            .branch(&self.name)
+            .ok_or_else(|| PyValueError::new_err("Branch not found"))?;
+        let range = entry_range(tree.entries() as usize, entry_start, entry_stop)?;
+
+        let iter = ColumnIter::new(branch, range.start, char_as_string)?;
+        match iter {
+            Some(mut iter) => iter.next_list(py, range.len()),
+            None => Err(PyValueError::new_err(format!(
+                "Unsupported branch type: {}",
+                branch.item_type_name()
+            ))),
+        }
+    }
+
+    /// Reads the entries at `indices`, in that order. Entries between the
+    /// smallest and largest index are read and the rest dropped.
+    #[pyo3(signature = (indices, char_as_string = false))]
+    fn take(&self, py: Python, indices: Vec<i64>, char_as_string: bool) -> PyResult<Py<PyAny>> {
+        let tree = self.file.tree(&self.tree_name)?;
+        let branch = tree
+            .branch(&self.name)
+            .ok_or_else(|| PyValueError::new_err("Branch not found"))?;
+        let (offsets, range) = take_range(&indices, tree.entries() as usize)?;
+
+        let array = branch_to_numpy(py, branch, range, char_as_string)?.ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "Unsupported branch type: {}",
+                branch.item_type_name()
+            ))
+        })?;
+        take_entries(py, array, &offsets)
+    }
+
+    #[getter]
+    fn typename(&self) -> PyResult<String> {
+        let tree = self.file.tree(&self.tree_name)?;
+        let branch = tree
+            .branch(&self.name)
+            .ok_or_else(|| PyValueError::new_err("Branch not found"))?;
+        Ok(branch.item_type_name())
+    }
+
+    /// Returns each entry's bytes as stored, uncompressed but not interpreted,
+    /// for payloads oxyroot can't decode. Numbers are big-endian, strings keep
+    /// their length prefix, and entries of objects and containers start with
+    /// ROOT's own headers.
+    #[pyo3(signature = (entry_start = None, entry_stop = None))]
+    fn raw(
+        &self,
+        py: Python,
+        entry_start: Option<usize>,
+        entry_stop: Option<usize>,
+    ) -> PyResult<Py<PyList>> {
+        let tree = self.file.tree(&self.tree_name)?;
+        let branch = tree
+            .branch(&self.name)
+            .ok_or_else(|| PyValueError::new_err("Branch not found"))?;
+        let range = entry_range(tree.entries() as usize, entry_start, entry_stop)?;
+
+        // Fixed-width entries and strings share one buffer per basket, so they
+        // are cut by width or length prefix; everything else is handed over
+        // one entry per buffer, which is then read to its end.
+        let type_name = branch.item_type_name();
+        if type_name == "unknown" {
+            return Err(PyValueError::new_err(format!(
+                "Cannot split branch '{}' into entries: it has several leaves",
+                self.name
+            )));
+        }
+        let width = entry_width(&type_name);
+        let string = dtype_name(&type_name).as_deref() == Some("str");
+        let entries = branch
+            .get_basket(move |r| {
+                if string {
+                    let mut entry = vec![r.read_u8()?];
+                    let n = match entry[0] {
+                        255 => {
+                            let n = r.read_u32()?;
+                            entry.extend(n.to_be_bytes());
+                            n as usize
+                        }
+                        n => n as usize,
+                    };
+                    entry.extend(r.read_array_u8(n)?);
+                    return Ok(entry);
+                }
+                let n = width.unwrap_or(r.len().max(0) as usize);
+                r.read_array_u8(n).map(<[u8]>::to_vec)
+            })
+            .skip(range.start)
+            .take(range.len());
+
+        let list = PyList::empty(py);
+        for entry in entries {
+            let entry = entry.map_err(|e| PyValueError::new_err(e.to_string()))?;
+            list.append(PyBytes::new(py, &entry))?;
+        }
+        Ok(list.unbind())
+    }
+
+    /// Whether the branch is present in its tree; `tree["name"]` doesn't check.
+    fn exists(&self) -> PyResult<bool> {
+        let tree = self.file.tree(&self.tree_name)?;
+        Ok(tree.branch(&self.name).is_some())
+    }
+
+    /// Whether each entry holds several values: a fixed-size array such as
+    /// `float[3]`, a variable-length `float[]` or a `vector`.
+    #[getter]
+    fn is_array(&self) -> PyResult<bool> {
+        let typename = self.typename()?;
+        Ok(fixed_array(&typename).is_some()
+            || typename.ends_with("[]")
+            || typename.starts_with("vector<"))
+    }
+
+    /// Values per entry: 1 for scalars and strings, `N` for `T[N]`, `None`
+    /// when the length varies from entry to entry.
+    #[getter]
+    fn leaf_count(&self) -> PyResult<Option<usize>> {
+        Ok(leaf_count(&self.typename()?))
+    }
+
+    /// Leaves out the type if the tree can't be read or is in a remote file
+    /// not downloaded yet.
+    fn __repr__(&self) -> String {
+        let typename = match self.file.is_fetched() {
+            true => self.typename().ok(),
+            false => None,
+        };
+        match typename {
+            Some(typename) => format!(
+                "<Branch '{}' ({}) of tree '{}' in '{}'>",
+                self.name, typename, self.tree_name, self.path
+            ),
+            None => format!(
+                "<Branch '{}' of tree '{}' in '{}'>",
+                self.name, self.tree_name, self.path
+            ),
+        }
+    }
+
+    /// Branches compare by file path, tree name and branch name.
+    fn __eq__(&self, other: PyRef<Self>) -> bool {
+        (&self.path, &self.tree_name, &self.name) == (&other.path, &other.tree_name, &other.name)
+    }
+
+    fn __hash__(&self) -> u64 {
+        hash_key(&(&self.path, &self.tree_name, &self.name))
+    }
+}
+
+/// Hashes the fields a `__eq__` compares, for the matching `__hash__`.
+fn hash_key(key: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The `size_estimate()` of reading `entries` entries of the named branches,
+/// a leaf-list branch counting all its leaves; missing and unsupported ones
+/// count as nothing.
+fn estimate_size(tree: &ReaderTree, leaves: &LeafLists, names: &[String], entries: usize) -> usize {
+    names
+        .iter()
+        .filter_map(|name| {
+            if let Some(leaves) = leaves.get(name) {
+                return Some(
+                    entries
+                        * leaves
+                            .iter()
+                            .filter_map(|l| leaf_size(l.code))
+                            .sum::<usize>(),
+                );
+            }
+            let type_name = tree.branch(name)?.item_type_name();
+            dtype_name(&type_name)?;
+            Some(entries * entry_width(&type_name).unwrap_or(8))
+        })
+        .sum()
+}
+
+/// Raises `MemoryError` if reading about `needed` bytes would go over the
+/// limit set with `set_memory_limit()`, before anything is read.
+fn check_memory_limit(needed: usize) -> PyResult<()> {
+    let limit = MEMORY_LIMIT.load(Ordering::Relaxed);
+    if limit > 0 && needed > limit {
+        return Err(PyMemoryError::new_err(format!(
+            "Reading needs about {} bytes, over the memory limit of {} bytes; \
+             select fewer columns or entries, or read smaller batches with iter_batches()",
+            needed, limit
+        )));
+    }
+    Ok(())
+}
+
+/// Decompresses an object of `len` bytes stored the way ROOT compresses keys
+/// and baskets: a series of blocks, each with a 9-byte header of the algorithm
+/// followed by the compressed and uncompressed sizes, three bytes each.
+fn root_decompress(mut data: &[u8], len: usize) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(len);
+    while out.len() < len {
+        let (header, rest) = data
+            .split_at_checked(9)
+            .ok_or("compressed block is truncated")?;
+        let size = |b: &[u8]| b[0] as usize | (b[1] as usize) << 8 | (b[2] as usize) << 16;
+        let (compressed, uncompressed) = (size(&header[3..6]), size(&header[6..9]));
+        let (block, rest) = rest
+            .split_at_checked(compressed)
+            .ok_or("compressed block is truncated")?;
+        let mut reader: Box<dyn std::io::Read> = match &header[..2] {
+            b"ZL" => Box::new(flate2::read::ZlibDecoder::new(block)),
+            b"XZ" => Box::new(xz2::read::XzDecoder::new(block)),
+            b"ZS" => Box::new(zstd::stream::read::Decoder::new(block).map_err(|e| e.to_string())?),
+            // LZ4 blocks start with an 8-byte checksum.
+            b"L4" => {
+                let block = block.get(8..).ok_or("compressed block is truncated")?;
+                let block = lz4::block::decompress(block, Some(uncompressed as i32))
+                    .map_err(|e| e.to_string())?;
+                Box::new(std::io::Cursor::new(block))
+            }
+            other => {
+                return Err(format!(
+                    "unsupported compression '{}'",
+                    String::from_utf8_lossy(other)
+                ))
+            }
+        };
+        let start = out.len();
+        reader.read_to_end(&mut out).map_err(|e| e.to_string())?;
+        if out.len() - start != uncompressed {
+            return Err("compressed block has the wrong size".to_string());
+        }
+        data = rest;
+    }
+    Ok(out)
+}
+
+/// Sums the uncompressed and compressed sizes of each column over the row
+/// groups of a written parquet file, the leaves of list columns together.
+fn parquet_summary<'py>(
+    py: Python<'py>,
+    metadata: &parquet::format::FileMetaData,
+    codec: &str,
+) -> PyResult<Bound<'py, PyDict>> {
+    let mut sizes: Vec<(&str, i64, i64)> = Vec::new();
+    for column in metadata.row_groups.iter().flat_map(|group| &group.columns) {
+        let Some(meta) = &column.meta_data else {
+            continue;
+        };
+        let name = meta.path_in_schema.first().map_or("", String::as_str);
+        let index = match sizes.iter().position(|(n, _, _)| *n == name) {
+            Some(index) => index,
+            None => {
+                sizes.push((name, 0, 0));
+                sizes.len() - 1
+            }
+        };
+        sizes[index].1 += meta.total_uncompressed_size;
+        sizes[index].2 += meta.total_compressed_size;
+    }
+
+    let summary = PyDict::new(py);
+    for (name, uncompressed, compressed) in sizes {
+        let column = PyDict::new(py);
+        column.set_item("codec", codec)?;
+        column.set_item("uncompressed_bytes", uncompressed)?;
+        column.set_item("compressed_bytes", compressed)?;
+        column.set_item(
+            "ratio",
+            (compressed > 0).then(|| uncompressed as f64 / compressed as f64),
+        )?;
+        summary.set_item(name, column)?;
+    }
+    Ok(summary)
+}
+
+/// Names ROOT's compression algorithm codes, as in `ROOT::RCompressionSetting`.
+fn compression_algorithm(code: i32) -> &'static str {
+    match code {
+        0 => "global",
+        1 => "zlib",
+        2 => "lzma",
+        3 => "old",
+        4 => "lz4",
+        5 => "zstd",
+        _ => "unknown",
+    }
+}
+
+/// Maps a codec name and optional level to parquet compression. A level is
+/// only accepted for gzip, brotli and zstd.
+fn parquet_compression(name: &str, level: Option<i32>) -> PyResult<Compression> {
+    let to_err = |e: parquet::errors::ParquetError| PyValueError::new_err(e.to_string());
+    let unsigned = |level: i32| {
+        u32::try_from(level).map_err(|_| {
+            PyValueError::new_err(format!("Invalid compression level {} for {}", level, name))
+        })
+    };
+    Ok(match (name, level) {
+        ("gzip", None) => Compression::GZIP(GzipLevel::default()),
+        ("gzip", Some(level)) => {
+            Compression::GZIP(GzipLevel::try_new(unsigned(level)?).map_err(to_err)?)
+        }
+        ("brotli", None) => Compression::BROTLI(BrotliLevel::default()),
+        ("brotli", Some(level)) => {
+            Compression::BROTLI(BrotliLevel::try_new(unsigned(level)?).map_err(to_err)?)
+        }
+        ("zstd", None) => Compression::ZSTD(ZstdLevel::default()),
+        ("zstd", Some(level)) => Compression::ZSTD(ZstdLevel::try_new(level).map_err(to_err)?),
+        (_, Some(_)) if ["snappy", "uncompressed", "lzo", "lz4"].contains(&name) => {
+            return Err(PyValueError::new_err(format!(
+                "compression_level is not supported for {}",
+                name
+            )));
+        }
+        ("snappy", None) => Compression::SNAPPY,
+        ("uncompressed", None) => Compression::UNCOMPRESSED,
+        ("lzo", None) => Compression::LZO,
+        ("lz4", None) => Compression::LZ4,
+        _ => return Err(PyValueError::new_err("Invalid compression type")),
+    })
+}
+
+/// Resolves a `columns` argument against the tree's branch names. Entries
+/// containing `*`, `?` or `[` are shell-style patterns expanded in branch order;
+/// other entries, and patterns matching nothing, are kept as given so the
+/// caller reports them as missing.
+fn select_branches(all: Vec<String>, columns: Option<Vec<String>>) -> PyResult<Vec<String>> {
+    let Some(columns) = columns else {
+        return Ok(all);
+    };
+    let mut selected: Vec<String> = Vec::new();
+    for column in columns {
+        let matches = if column.contains(['*', '?', '[']) {
+            let pattern =
+                glob::Pattern::new(&column).map_err(|e| PyValueError::new_err(e.to_string()))?;
+            all.iter()
+                .filter(|name| pattern.matches(name))
+                .cloned()
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let matches = if matches.is_empty() {
+            vec![column]
+        } else {
+            matches
+        };
+        for name in matches {
+            if !selected.contains(&name) {
+                selected.push(name);
+            }
+        }
+    }
+    Ok(selected)
+}
+
+/// Like `select_branches`, then drops the branches `ignore_columns` selects,
+/// which takes the same names or patterns.
+fn select_ignoring(
+    all: Vec<String>,
+    columns: Option<Vec<String>>,
+    ignore_columns: Option<Vec<String>>,
+) -> PyResult<Vec<String>> {
+    let mut columns = select_branches(all.clone(), columns)?;
+    if let Some(ignore_columns) = ignore_columns {
+        let ignored = select_branches(all, Some(ignore_columns))?;
+        columns.retain(|name| !ignored.contains(name));
+    }
+    Ok(columns)
+}
+
+/// The element type and length `N` of a fixed-size `T[N]` branch type.
+fn fixed_array(type_name: &str) -> Option<(&str, usize)> {
+    let (element, len) = type_name.strip_suffix(']')?.split_once('[')?;
+    Some((element, len.parse().ok()?))
+}
+
+/// Values per entry of a branch type, as `Branch.leaf_count` reports it.
+fn leaf_count(type_name: &str) -> Option<usize> {
+    if let Some((_, n)) = fixed_array(type_name) {
+        return Some(n);
+    }
+    let variable = type_name.ends_with("[]") || type_name.starts_with("vector<");
+    (!variable).then_some(1)
+}
+
+/// Describes `branch` and, under `"branches"`, its sub-branches.
+fn describe_branch<'py>(py: Python<'py>, branch: &Branch) -> PyResult<Bound<'py, PyDict>> {
+    let type_name = branch.item_type_name();
+    let info = PyDict::new(py);
+    info.set_item("typename", &type_name)?;
+    info.set_item("class", branch.class())?;
+    info.set_item("dtype", dtype_name(&type_name))?;
+    info.set_item("leaf_count", leaf_count(&type_name))?;
+    info.set_item("entries", branch.entries())?;
+    let branches = PyDict::new(py);
+    for sub in branch.branches() {
+        branches.set_item(sub.name(), describe_branch(py, sub)?)?;
+    }
+    info.set_item("branches", branches)?;
+    Ok(info)
+}
+
+thread_local! {
+    /// The first error hit by a read in a `Branch::get_basket` closure on this
+    /// thread, which has to return a value; `ColumnIter` raises it once the
+    /// values are collected.
+    static READ_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Reads a value of `branch` with `read` in a `get_basket` closure, which
+/// takes `bytes` of the entry. oxyroot panics on reads past the end of the
+/// buffer, so a short entry is caught first; either failure is recorded for
+/// `read_error()` and a placeholder returned.
+fn read_value<T: Default, E: std::fmt::Display>(
+    branch: &Branch,
+    r: &mut RBuffer,
+    bytes: usize,
+    read: impl FnOnce(&mut RBuffer) -> Result<T, E>,
+) -> T {
+    let value = match r.len() >= bytes as i64 {
+        true => read(r).map_err(|e| e.to_string()),
+        false => Err("the entry ends early".to_string()),
+    };
+    value.unwrap_or_else(|e| {
+        READ_ERROR.with_borrow_mut(|error| {
+            error
+                .get_or_insert_with(|| format!("Failed to read branch '{}': {}", branch.name(), e));
+        });
+        T::default()
+    })
+}
+
+/// Raises the error `read_value()` recorded on this thread, if any.
+fn read_error() -> PyResult<()> {
+    match READ_ERROR.take() {
+        Some(error) => Err(PyValueError::new_err(error)),
+        None => Ok(()),
+    }
+}
+
+/// Reads a fixed-size array branch from entry `start`, `n` values per entry.
+fn fixed_array_iter<'a, T: Default + 'a, E: std::fmt::Display>(
+    branch: &'a Branch,
+    start: usize,
+    n: usize,
+    read: impl Fn(&mut RBuffer) -> Result<T, E> + 'a,
+) -> Box<dyn Iterator<Item = Vec<T>> + 'a> {
+    Box::new(
+        branch
+            .get_basket(move |r| {
+                (0..n)
+                    .map(|_| read_value(branch, r, size_of::<T>(), &read))
+                    .collect()
+            })
+            .skip(start),
+    )
+}
+
+/// One leaf of a leaf-list branch, e.g. `n/I` in `x/F:y/F:n/I`: its name and
+/// ROOT type code.
+struct Leaf {
+    name: String,
+    code: char,
+}
+
+type LeafLists = HashMap<String, Vec<Leaf>>;
+
+/// Bytes a scalar leaf of ROOT type `code` takes, or `None` for strings,
+/// `Float16_t`, `Double32_t` and unknown codes.
+fn leaf_size(code: char) -> Option<usize> {
+    Some(match code {
+        'B' | 'b' | 'O' => 1,
+        'S' | 's' => 2,
+        'I' | 'i' | 'F' => 4,
+        'D' | 'L' | 'l' | 'G' | 'g' => 8,
+        _ => return None,
+    })
+}
+
+/// The numpy dtype name a leaf of ROOT type `code` is read as.
+fn leaf_dtype(code: char) -> &'static str {
+    match code {
+        'B' => "int8",
+        'b' => "uint8",
+        'O' => "bool",
+        'S' => "int16",
+        's' => "uint16",
+        'I' => "int32",
+        'i' => "uint32",
+        'F' => "float32",
+        'D' => "float64",
+        'L' | 'G' => "int64",
+        _ => "uint64",
+    }
+}
+
+/// Parses a leaf list such as `x/F:y/F:n/I` into its leaves, or returns
+/// `None` unless it lists at least two scalar leaves of supported types. As
+/// in ROOT, a leaf without a type takes the previous one's, the first `F`.
+fn parse_leaf_list(title: &str) -> Option<Vec<Leaf>> {
+    let mut leaves = Vec::new();
+    let mut code = 'F';
+    for part in title.split(':') {
+        let name = match part.split_once('/') {
+            Some((name, type_code)) => {
+                let mut chars = type_code.chars();
+                code = chars.next()?;
+                if chars.next().is_some() {
+                    return None;
+                }
+                name
+            }
+            None => part,
+        };
+        let valid_name =
+            !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_name || leaf_size(code).is_none() {
+            return None;
+        }
+        leaves.push(Leaf {
+            name: name.to_string(),
+            code,
+        });
+    }
+    (leaves.len() > 1).then_some(leaves)
+}
+
+/// Finds the leaf lists of the branches in `names` in the streamed bytes of
+/// their tree, in one pass. A branch is streamed starting with its name and
+/// then its title, which ROOT sets to the leaf list, as length-prefixed
+/// strings; names found elsewhere aren't followed by a leaf list.
+fn find_leaf_lists(data: &[u8], names: &HashSet<&str>) -> LeafLists {
+    let mut lengths = [false; 255];
+    for name in names.iter().filter(|name| name.len() < 255) {
+        lengths[name.len()] = true;
+    }
+    let title_at = |rest: &[u8]| -> Option<Vec<Leaf>> {
+        let title = match *rest.first()? as usize {
+            255 => {
+                let len = u32::from_be_bytes(rest.get(1..5)?.try_into().ok()?) as usize;
+                rest.get(5..5 + len)?
+            }
+            len => rest.get(1..1 + len)?,
+        };
+        parse_leaf_list(std::str::from_utf8(title).ok()?)
+    };
+
+    let mut leaf_lists = HashMap::new();
+    for (i, &len) in data.iter().enumerate() {
+        let len = len as usize;
+        if len == 255 || !lengths[len] {
+            continue;
+        }
+        let Some(name) = data
+            .get(i + 1..i + 1 + len)
+            .and_then(|name| std::str::from_utf8(name).ok())
+            .filter(|name| names.contains(name) && !leaf_lists.contains_key(*name))
+        else {
+            continue;
+        };
+        if let Some(leaves) = title_at(&data[i + 1 + len..]) {
+            leaf_lists.insert(name.to_string(), leaves);
+        }
+    }
+    leaf_lists
+}
+
+/// Reads leaf `index` of a leaf-list branch from entry `start`. Each entry
+/// holds every leaf's value in turn, all of which are consumed, as entries may
+/// share one buffer.
+fn leaf_iter<'a>(
+    branch: &'a Branch,
+    start: usize,
+    leaves: &[Leaf],
+    index: usize,
+) -> ColumnIter<'a> {
+    fn values<'a, T: Default + 'a, E: std::fmt::Display>(
+        branch: &'a Branch,
+        start: usize,
+        before: usize,
+        after: usize,
+        read: impl Fn(&mut RBuffer) -> Result<T, E> + 'a,
+    ) -> Box<dyn Iterator<Item = T> + 'a> {
+        Box::new(
+            branch
+                .get_basket(move |r| {
+                    read_value(branch, r, before + size_of::<T>() + after, |r| {
+                        r.set_pos(r.pos() + before as i64);
+                        let value = read(r);
+                        r.set_pos(r.pos() + after as i64);
+                        value
+                    })
+                })
+                .skip(start),
+        )
+    }
+
+    let size = |leaf: &Leaf| leaf_size(leaf.code).unwrap_or(0);
+    let before = leaves[..index].iter().map(size).sum();
+    let after = leaves[index + 1..].iter().map(size).sum();
+    match leaves[index].code {
+        'B' => ColumnIter::Int8(values(branch, start, before, after, |r| r.read_i8())),
+        'b' => ColumnIter::UInt8(values(branch, start, before, after, |r| r.read_u8())),
+        'O' => ColumnIter::Bool(values(branch, start, before, after, |r| r.read_bool())),
+        'S' => ColumnIter::Int16(values(branch, start, before, after, |r| r.read_i16())),
+        's' => ColumnIter::UInt16(values(branch, start, before, after, |r| r.read_u16())),
+        'I' => ColumnIter::Int32(values(branch, start, before, after, |r| r.read_i32())),
+        'i' => ColumnIter::UInt32(values(branch, start, before, after, |r| r.read_u32())),
+        'F' => ColumnIter::Float(values(branch, start, before, after, |r| r.read_f32())),
+        'D' => ColumnIter::Double(values(branch, start, before, after, |r| r.read_f64())),
+        'L' | 'G' => ColumnIter::Int64(values(branch, start, before, after, |r| r.read_i64())),
+        _ => ColumnIter::UInt64(values(branch, start, before, after, |r| r.read_u64())),
+    }
+}
+
+/// The numpy dtype name for a branch type: `"str"` for strings and
+/// `"list[<dtype>]"` for jagged and fixed-size array branches.
+fn dtype_name(type_name: &str) -> Option<String> {
+    if let Some(inner) = type_name
+        .strip_prefix("vector<")
+        .and_then(|t| t.strip_suffix('>'))
+    {
+        return match inner {
+            "string" => None,
+            _ if inner.starts_with("vector<") => None,
+            _ => dtype_name(inner).map(|dtype| format!("list[{}]", dtype)),
+        };
+    }
+    let dtype = match type_name {
+        "float" => "float32",
+        "double" => "float64",
+        "int8_t" => "int8",
+        "int16_t" => "int16",
+        "int32_t" => "int32",
+        "int64_t" | "Long64_t" => "int64",
+        "uint8_t" => "uint8",
+        "uint16_t" => "uint16",
+        "uint32_t" => "uint32",
+        "uint64_t" | "ULong64_t" => "uint64",
+        "bool" => "bool",
+        "string" | "char*" | "TString" => "str",
+        _ => {
+            return match fixed_array(type_name)? {
+                ("bool" | "string", _) => None,
+                (element, _) => dtype_name(element).map(|dtype| format!("list[{}]", dtype)),
+            }
+        }
+    };
+    Some(dtype.to_string())
+}
+
+/// Bytes per value of a numeric or bool dtype as named by `dtype_name`.
+fn dtype_width(dtype: &str) -> Option<usize> {
+    Some(match dtype {
+        "bool" | "int8" | "uint8" => 1,
+        "int16" | "uint16" => 2,
+        "float32" | "int32" | "uint32" => 4,
+        "float64" | "int64" | "uint64" => 8,
+        _ => return None,
+    })
+}
+
+/// Bytes per entry of a fixed-width branch type, including `T[N]` arrays, or
+/// `None` for strings and variable-length types.
+fn entry_width(type_name: &str) -> Option<usize> {
+    match fixed_array(type_name) {
+        Some((element, n)) => Some(n * entry_width(element)?),
+        None => dtype_width(&dtype_name(type_name)?),
+    }
+}
+
+/// Reports a branch that cannot be read: an error when `strict`, otherwise an
+/// `OxyrootWarning` and the caller skips the branch. The warning raises
+/// instead if warnings filters turn it into an error.
+fn skip_branch(strict: bool, message: String) -> PyResult<()> {
+    if strict {
+        return Err(PyValueError::new_err(message));
+    }
+    Python::attach(|py| {
+        let message = CString::new(format!("{}, skipping", message))?;
+        PyErr::warn(py, &py.get_type::<OxyrootWarning>(), &message, 1)
+    })
+}
+
+/// Describes a branch whose entry count differs from its tree's, as happens
+/// with corrupt files; reading it would misalign rows.
+fn entries_mismatch(tree: &ReaderTree, branch: &Branch) -> Option<String> {
+    (branch.entries() != tree.entries()).then(|| {
+        format!(
+            "Branch '{}' has {} entries but the tree has {}",
+            branch.name(),
+            branch.entries(),
+            tree.entries()
+        )
+    })
+}
+
+fn unsupported_branch(branch: &Branch) -> String {
+    format!(
+        "Unsupported branch type: {} (branch '{}')",
+        branch.item_type_name(),
+        branch.name()
+    )
+}
+
+/// Resolves optional `entry_start`/`entry_stop` bounds against a tree of
+/// `num_entries` entries, clamping the stop to the tree length.
+fn entry_range(
+    num_entries: usize,
+    entry_start: Option<usize>,
+    entry_stop: Option<usize>,
+) -> PyResult<Range<usize>> {
+    let start = entry_start.unwrap_or(0);
+    if let Some(stop) = entry_stop {
+        if start > stop {
+            return Err(PyValueError::new_err(format!(
+                "entry_start ({}) is greater than entry_stop ({})",
+                start, stop
+            )));
+        }
+    }
+    let stop = entry_stop.unwrap_or(num_entries).min(num_entries);
+    Ok(start.min(stop)..stop)
+}
+
+/// Resolves entry `indices`, negative ones counting from the end, into the
+/// range of entries covering them and each index's offset into that range.
+fn take_range(indices: &[i64], num_entries: usize) -> PyResult<(Vec<usize>, Range<usize>)> {
+    let indices = indices
+        .iter()
+        .map(|&index| {
+            let resolved = if index < 0 {
+                num_entries as i64 + index
+            } else {
+                index
+            };
+            usize::try_from(resolved)
+                .ok()
+                .filter(|&resolved| resolved < num_entries)
+                .ok_or_else(|| {
+                    PyValueError::new_err(format!(
+                        "Entry {} is out of range for {} entries",
+                        index, num_entries
+                    ))
+                })
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+
+    let start = indices.iter().copied().min().unwrap_or(0);
+    let stop = indices.iter().map(|&index| index + 1).max().unwrap_or(0);
+    let offsets = indices.iter().map(|&index| index - start).collect();
+    Ok((offsets, start..stop))
+}
+
+/// Picks the entries at `offsets` out of a column read by `ColumnIter`.
+fn take_entries(py: Python, column: Py<PyAny>, offsets: &[usize]) -> PyResult<Py<PyAny>> {
+    let column = column.bind(py);
+    if let Ok(list) = column.downcast::<PyList>() {
+        let entries = offsets
+            .iter()
+            .map(|&offset| list.get_item(offset))
+            .collect::<PyResult<Vec<_>>>()?;
+        return Ok(PyList::new(py, entries)?.into_any().unbind());
+    }
+    Ok(column.call_method1("take", (offsets.to_vec(),))?.unbind())
+}
+
+/// Reads `range` of `branch` into a numpy array (a list for strings), or
+/// `None` if the branch type is not supported.
+fn branch_to_numpy(
+    py: Python,
+    branch: &Branch,
+    range: Range<usize>,
+    char_as_string: bool,
+) -> PyResult<Option<Py<PyAny>>> {
+    match ColumnIter::new(branch, range.start, char_as_string)? {
+        Some(mut iter) => Ok(Some(iter.next_batch(py, range.len())?)),
+        None => Ok(None),
+    }
+}
+
+impl PyBranch {
+    /// Reads the branch as `array()` does after checking it has `dtype`.
+    fn array_as(
+        &self,
+        py: Python,
+        dtype: &str,
+        entry_start: Option<usize>,
+        entry_stop: Option<usize>,
+    ) -> PyResult<Py<PyAny>> {
+        let tree = self.file.tree(&self.tree_name)?;
+        let branch = tree
+            .branch(&self.name)
+            .ok_or_else(|| PyValueError::new_err("Branch not found"))?;
+        let type_name = branch.item_type_name();
+        if dtype_name(&type_name).as_deref() != Some(dtype) {
+            return Err(PyValueError::new_err(format!(
+                "Branch '{}' has type {}, not {}",
+                self.name, type_name, dtype
+            )));
+        }
+        self.array(py, entry_start, entry_stop, false, 1, None)
+    }
+}
+
+#[pymethods]
+impl PyMultiFile {
+    fn keys(&self) -> PyResult<Vec<String>> {
+        let mut keys: Vec<String> = Vec::new();
+        for file in &self.files {
+            for key in
+                file.with_file(|file| file.keys_name().map(|k| k.to_string()).collect::<Vec<_>>())?
+            {
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+        }
+        Ok(keys)
+    }
+
+    fn __getitem__(&self, name: &str) -> PyResult<PyChain> {
+        Ok(PyChain {
+            name: name.to_string(),
+            files: self.files.clone(),
+        })
+    }
+}
+
+#[pymethods]
+impl PyChain {
+    /// Starts a chain of `name` over `paths`, like ROOT's `TChain`; more
+    /// files can be added with `add()`.
+    #[new]
+    #[pyo3(signature = (name, paths = None))]
+    fn new(name: String, paths: Option<Vec<String>>) -> PyResult<Self> {
+        let mut chain = PyChain {
+            name,
+            files: Vec::new(),
+        };
+        for path in paths.into_iter().flatten() {
+            chain.add(path)?;
+        }
+        Ok(chain)
+    }
+
+    /// Appends the file at `path`, or every file matching it if it is a glob
+    /// pattern, and returns how many were added. Files are only read on use.
+    fn add(&mut self, path: String) -> PyResult<usize> {
+        let paths = expand_paths(vec![path])?;
+        self.files.extend(
+            paths
+                .iter()
+                .map(|path| Rc::new(FileHandle::new(path.clone()))),
+        );
+        Ok(paths.len())
+    }
+
+    #[getter]
+    fn paths(&self) -> Vec<String> {
+        self.files.iter().map(|file| file.path.clone()).collect()
+    }
+
+    /// Branches of the first file the tree can be read from.
+    fn branches(&self) -> PyResult<Vec<String>> {
+        for file in &self.files {
+            if let Ok(tree) = file.tree(&self.name) {
+                return Ok(tree.branches().map(|b| b.name().to_string()).collect());
+            }
+        }
+        Ok(Vec::new())
+    }
+
+    #[getter]
+    fn num_entries(&self, py: Python) -> PyResult<usize> {
+        let mut num_entries = 0;
+        for (_, tree) in self.trees(py, false)? {
+            num_entries += tree.entries() as usize;
+        }
+        Ok(num_entries)
+    }
+
+    /// Paths of the files that fail to open or don't contain the tree.
+    fn failed_paths(&self) -> Vec<String> {
+        self.files
+            .iter()
+            .filter(|file| file.tree(&self.name).is_err())
+            .map(|file| file.path.clone())
+            .collect()
+    }
+
+    /// Reads the selected branches of every file and concatenates them in file
+    /// order. A branch missing or unsupported in any file is left out, as are
+    /// files the tree can't be read from; with `strict` either raises instead.
+    /// Raises if no file can be read, rather than returning an empty dict.
+    /// `progress` is called with `(done, total)` as each file is read. Every
+    /// file must have the branches in `require_columns`. With `fill_value`, a
+    /// selected branch missing from some files is kept and filled with it for
+    /// their entries, and the branches of every file are candidates, not only
+    /// those of the first. With `promote_ints`, integer columns are widened to
+    /// `int64` or `uint64` in every file, so a branch stored as `int32_t` in
+    /// some files and `int64_t` in others always comes back as `int64`.
+    /// `add_source_column` names an extra column, a list holding the path of
+    /// the file each entry was read from. Leaf-list branches give a column per
+    /// leaf, as in `Tree.arrays()`.
+    #[pyo3(signature = (columns = None, strict = false, progress = None, require_columns = None, fill_value = None, promote_ints = false, add_source_column = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn arrays(
+        &self,
+        py: Python,
+        columns: Option<Vec<String>>,
+        strict: bool,
+        progress: Option<&Bound<'_, PyAny>>,
+        require_columns: Option<Vec<String>>,
+        fill_value: Option<Py<PyAny>>,
+        promote_ints: bool,
+        add_source_column: Option<String>,
+    ) -> PyResult<Py<PyDict>> {
+        let trees = self.trees(py, strict)?;
+        if trees.is_empty() {
+            return Err(PyValueError::new_err(format!(
+                "No file contains a readable tree '{}'",
+                self.name
+            )));
+        }
+        // With `fill_value` a branch of any file can be filled in the others,
+        // so the candidates are all files' branches in order of appearance.
+        let candidates = match fill_value {
+            Some(_) => &trees[..],
+            None => &trees[..1],
+        };
+        let mut all_branches = Vec::new();
+        for (_, tree) in candidates {
+            for branch in tree.branches() {
+                if !all_branches.iter().any(|name| name == branch.name()) {
+                    all_branches.push(branch.name().to_string());
+                }
+            }
+        }
+        let branches_to_read = select_branches(all_branches, columns)?;
+        let mut needed = 0;
+        for (file, tree) in &trees {
+            let leaves = file.leaf_lists(&self.name)?;
+            needed += estimate_size(tree, &leaves, &branches_to_read, tree.entries() as usize);
+        }
+        check_memory_limit(needed)?;
+
+        // Each part is a file's entry count and the branch's columns in it, one
+        // per leaf for a leaf list, `None` where the file lacks the branch and
+        // `fill_value` stands in.
+        let mut parts = branches_to_read
+            .into_iter()
+            .map(|name| (name, Some(Vec::new())))
+            .collect::<Vec<(String, Option<Vec<ChainPart>>)>>();
+        let total = trees.len();
+        let mut sources = Vec::new();
+        for (done, (file, tree)) in trees.into_iter().enumerate() {
+            let entries = tree.entries() as usize;
+            let leaves = file.leaf_lists(&self.name)?;
+            sources.push((PyString::new(py, &file.path), entries));
+            for name in require_columns.iter().flatten() {
+                if tree.branch(name).is_none() {
+                    return Err(PyValueError::new_err(format!(
+                        "Required branch '{}' not found in '{}'",
+                        name, file.path
+                    )));
+                }
+            }
+            for (branch_name, column) in parts.iter_mut() {
+                py.check_signals()?;
+                let Some(arrays) = column else {
+                    continue;
+                };
+                let branch = match tree.branch(branch_name) {
+                    Some(branch) => branch,
+                    None if fill_value.is_some() => {
+                        arrays.push((entries, None));
+                        continue;
+                    }
+                    None => {
+                        skip_branch(
+                            strict,
+                            format!("Branch '{}' not found in '{}'", branch_name, file.path),
+                        )?;
+                        *column = None;
+                        continue;
+                    }
+                };
+                if let Some(message) = entries_mismatch(&tree, branch) {
+                    skip_branch(strict, format!("{} in '{}'", message, file.path))?;
+                    *column = None;
+                    continue;
+                }
+                let columns = match leaves.get(branch_name) {
+                    Some(branch_leaves) => (0..branch_leaves.len())
+                        .map(|index| {
+                            let name = format!("{}.{}", branch_name, branch_leaves[index].name);
+                            let array = leaf_iter(branch, 0, branch_leaves, index)
+                                .next_batch(py, entries)?;
+                            Ok((name, array))
+                        })
+                        .collect::<PyResult<Vec<_>>>()?,
+                    None => match branch_to_numpy(py, branch, 0..entries, false)? {
+                        Some(array) => vec![(branch_name.clone(), array)],
+                        None => {
+                            skip_branch(strict, unsupported_branch(branch))?;
+                            *column = None;
+                            continue;
+                        }
+                    },
+                };
+                let columns = match promote_ints {
+                    true => columns
+                        .into_iter()
+                        .map(|(name, array)| Ok((name, promote_int(py, array)?)))
+                        .collect::<PyResult<Vec<_>>>()?,
+                    false => columns,
+                };
+                arrays.push((entries, Some(columns)));
+            }
+            if let Some(progress) = progress {
+                progress.call1((done + 1, total))?;
+            }
+        }
+
+        let arrays = PyDict::new(py);
+        for (branch_name, column) in parts {
+            let Some(column) = column else {
+                continue;
+            };
+            // The first file read gives the column names, which others must
+            // share: a leaf list with other leaves elsewhere can't be joined.
+            let names = column
+                .iter()
+                .find_map(|(_, columns)| columns.as_ref())
+                .map(|columns| columns.iter().map(|(name, _)| name.clone()).collect())
+                .unwrap_or_else(|| vec![branch_name.clone()]);
+            let same_names = |columns: &Vec<(String, Py<PyAny>)>| {
+                columns.iter().map(|(name, _)| name).eq(names.iter())
+            };
+            if !column
+                .iter()
+                .all(|(_, columns)| columns.as_ref().is_none_or(same_names))
+            {
+                return Err(PyValueError::new_err(format!(
+                    "Branch '{}' has different leaves across files",
+                    branch_name
+                )));
+            }
+            for (index, name) in names.iter().enumerate() {
+                let template = column
+                    .iter()
+                    .find_map(|(_, columns)| columns.as_ref().map(|columns| &columns[index].1));
+                let mut filled = Vec::new();
+                for (entries, columns) in &column {
+                    filled.push(match (columns, &fill_value) {
+                        (Some(columns), _) => columns[index].1.clone_ref(py),
+                        (None, Some(fill_value)) => fill(py, template, *entries, fill_value)?,
+                        (None, None) => unreachable!("parts without a fill_value are always read"),
+                    });
+                }
+                arrays.set_item(name, concatenate(py, filled)?)?;
+            }
+        }
+        if let Some(name) = add_source_column {
+            if arrays.contains(&name)? {
+                return Err(PyValueError::new_err(format!(
+                    "add_source_column '{}' is also a branch name",
+                    name
+                )));
+            }
+            let paths = sources
+                .iter()
+                .flat_map(|(path, entries)| std::iter::repeat_n(path, *entries))
+                .collect::<Vec<_>>();
+            arrays.set_item(name, PyList::new(py, paths)?)?;
+        }
+        Ok(arrays.into())
+    }
+
+    /// Yields dicts of up to `batch_size` entries per branch, opening one file
+    /// at a time, so datasets larger than memory can be streamed.
+    /// `promote_ints` widens integer columns as in `arrays()`.
+    #[pyo3(signature = (batch_size, columns = None, strict = false, promote_ints = false))]
+    fn iter_batches(
+        &self,
+        batch_size: usize,
+        columns: Option<Vec<String>>,
+        strict: bool,
+        promote_ints: bool,
+    ) -> PyResult<PyChainBatchIterator> {
+        if batch_size == 0 {
+            return Err(PyValueError::new_err("batch_size must be greater than 0"));
+        }
+        Ok(PyChainBatchIterator {
+            name: self.name.clone(),
+            batch_size,
+            columns,
+            strict,
+            promote_ints,
+            files: self.files.clone().into_iter(),
+            current: None,
+        })
+    }
+}
+
+impl PyChain {
+    /// Opens the tree in every file, skipping (or, if `strict`, rejecting)
+    /// files that fail to open or don't contain it.
+    fn trees(&self, py: Python, strict: bool) -> PyResult<Vec<(&FileHandle, Rc<ReaderTree>)>> {
+        let mut trees = Vec::new();
+        for file in &self.files {
+            match file.tree(&self.name) {
+                Ok(tree) => trees.push((file.as_ref(), tree)),
+                Err(e) => skip_branch(
+                    strict,
+                    format!(
+                        "Cannot read '{}' from '{}': {}",
+                        self.name,
+                        file.path,
+                        e.value(py)
+                    ),
+                )?,
+            }
+        }
+        Ok(trees)
+    }
+}
+
+/// Widens a column read by `ColumnIter::next_batch` to `int64` if it holds
+/// signed integers or `uint64` if unsigned, entry by entry for jagged
+/// columns. Other columns are returned as they are.
+fn promote_int(py: Python, column: Py<PyAny>) -> PyResult<Py<PyAny>> {
+    let column = column.bind(py);
+    if let Ok(entries) = column.downcast::<PyList>() {
+        let promoted = entries
+            .iter()
+            .map(|entry| promote_int(py, entry.unbind()))
+            .collect::<PyResult<Vec<_>>>()?;
+        return Ok(PyList::new(py, promoted)?.into_any().unbind());
+    }
+    let Ok(dtype) = column.getattr("dtype") else {
+        return Ok(column.clone().unbind());
+    };
+    let target = match dtype.getattr("kind")?.extract::<String>()?.as_str() {
+        "i" => "int64",
+        "u" => "uint64",
+        _ => return Ok(column.clone().unbind()),
+    };
+    Ok(column.call_method1("astype", (target,))?.unbind())
+}
+
+/// `entries` copies of `fill_value`, shaped like `template`, a read of the same
+/// branch from another file: a list if it is one, otherwise a numpy array of
+/// its dtype.
+fn fill(
+    py: Python,
+    template: Option<&Py<PyAny>>,
+    entries: usize,
+    fill_value: &Py<PyAny>,
+) -> PyResult<Py<PyAny>> {
+    let template = template.map(|template| template.bind(py));
+    if template.is_some_and(|template| template.is_instance_of::<PyList>()) {
+        let values = PyList::new(py, (0..entries).map(|_| fill_value.clone_ref(py)))?;
+        return Ok(values.into_any().unbind());
+    }
+    let dtype = template
+        .map(|template| template.getattr("dtype"))
+        .transpose()?;
+    Ok(py
+        .import("numpy")?
+        .call_method1("full", (entries, fill_value, dtype))?
+        .unbind())
+}
+
+/// Joins per-file reads of one branch: numpy arrays with `numpy.concatenate`,
+/// lists (strings and jagged branches) by appending.
+fn concatenate(py: Python, parts: Vec<Py<PyAny>>) -> PyResult<Py<PyAny>> {
+    if parts
+        .iter()
+        .all(|part| part.bind(py).is_instance_of::<PyList>())
+    {
+        let joined = PyList::empty(py);
+        for part in parts {
+            for item in part.bind(py).try_iter()? {
+                joined.append(item?)?;
+            }
+        }
+        return Ok(joined.into_any().unbind());
+    }
+    let numpy = py.import("numpy")?;
+    Ok(numpy.call_method1("concatenate", (parts,))?.unbind())
+}
+
+/// Expands a path or glob pattern into the matching paths, sorted.
+fn expand_glob(pattern: &str) -> PyResult<Vec<String>> {
+    let paths = glob::glob(pattern)
+        .map_err(|e| PyValueError::new_err(e.to_string()))?
+        .filter_map(Result::ok)
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    if paths.is_empty() {
+        return Err(PyValueError::new_err(format!(
+            "No files match '{}'",
+            pattern
+        )));
+    }
+    Ok(paths)
+}
+
+/// Expands each path or glob pattern, keeping the given order. URLs are kept
+/// as given.
+fn expand_paths(patterns: Vec<String>) -> PyResult<Vec<String>> {
+    let mut paths = Vec::new();
+    for pattern in patterns {
+        if is_remote(&pattern) {
+            paths.push(pattern);
+        } else {
+            paths.extend(expand_glob(&pattern)?);
+        }
+    }
+    Ok(paths)
+}
+
+/// Opens a single ROOT file, or several when given a list of paths or a glob
+/// pattern. Files are only read on first use unless `validate` is set, in
+/// which case each one is opened straight away. Remote files are downloaded
+/// in full, with the GIL released, when first read.
+#[pyfunction]
+#[pyo3(signature = (path, validate = false))]
+fn open(py: Python, path: &Bound<'_, PyAny>, validate: bool) -> PyResult<Py<PyAny>> {
+    let patterns = if let Ok(path) = path.extract::<String>() {
+        if is_remote(&path) || !path.contains(['*', '?', '[']) {
+            let file = PyRootFile::new(path);
+            if validate {
+                file.file.validate(py)?;
+            }
+            return file.into_py_any(py);
+        }
+        vec![path]
+    } else {
+        path.extract::<Vec<String>>()?
+    };
+
+    let paths = expand_paths(patterns)?;
+    let files = paths
+        .iter()
+        .map(|path| Rc::new(FileHandle::new(path.clone())))
+        .collect::<Vec<_>>();
+    if validate {
+        for file in &files {
+            file.validate(py)?;
+        }
+    }
+    PyMultiFile { files, paths }.into_py_any(py)
+}
+
+/// Reads `tree_name` from the file at `path` in one call, the same as
+/// `open(path)[tree_name].arrays(columns)`.
+#[pyfunction]
+#[pyo3(signature = (path, tree_name, columns = None))]
+fn read(
+    py: Python,
+    path: &str,
+    tree_name: &str,
+    columns: Option<Vec<String>>,
+) -> PyResult<Py<PyDict>> {
+    PyRootFile::new(path.to_string())
+        .__getitem__(tree_name)?
+        .read_arrays(
+            py,
+            ReadOptions {
+                columns,
+                ..Default::default()
+            },
+        )
+}
+
+/// Opens a ROOT file held in memory, e.g. received over the network. The
+/// bytes are checked straight away.
+#[pyfunction]
+fn open_bytes(py: Python, data: &[u8]) -> PyResult<PyRootFile> {
+    let file = FileHandle::from_bytes(data)?;
+    file.validate(py)?;
+    Ok(PyRootFile {
+        path: file.path.clone(),
+        file: Rc::new(file),
+    })
+}
+
+/// Writes `tree_name` from every file matched by `paths` into `output_dir`,
+/// one parquet file per input named after it. With `partition_cols`, rows are
+/// split into Hive-style `column=value` directories and those columns are left
+/// out of the files. `progress` is called with `(done, total)` as each input
+/// file is written. Existing part files are only replaced with `overwrite`.
+/// Returns the paths written.
+#[pyfunction]
+#[pyo3(signature = (paths, tree_name, output_dir, partition_cols = None, compression = "zstd", progress = None, overwrite = false))]
+#[allow(clippy::too_many_arguments)]
+fn trees_to_parquet_dataset(
+    py: Python,
+    paths: &Bound<'_, PyAny>,
+    tree_name: &str,
+    output_dir: &str,
+    partition_cols: Option<Vec<String>>,
+    compression: &str,
+    progress: Option<&Bound<'_, PyAny>>,
+    overwrite: bool,
+) -> PyResult<Vec<String>> {
+    let patterns = match paths.extract::<String>() {
+        Ok(path) => vec![path],
+        Err(_) => paths.extract::<Vec<String>>()?,
+    };
+    let props = WriterProperties::builder()
+        .set_compression(parquet_compression(compression, None)?)
+        .set_max_row_group_size(DEFAULT_ROW_GROUP_SIZE)
+        .build();
+    let partition_cols = partition_cols.unwrap_or_default();
+
+    let paths = expand_paths(patterns)?;
+    let total = paths.len();
+    let mut written = Vec::new();
+    let mut stems = Vec::new();
+    for (done, path) in paths.into_iter().enumerate() {
+        py.check_signals()?;
+        let stem = Path::new(&path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if stems.contains(&stem) {
+            return Err(PyValueError::new_err(format!(
+                "Several input files are named '{}'",
+                stem
+            )));
+        }
+        let file = FileHandle::new(path);
+        let tree = file.tree(tree_name)?;
+        let (paths, skipped) = write_dataset_file(
+            &tree,
+            &*file.leaf_lists(tree_name)?,
+            Path::new(output_dir),
+            &stem,
+            &partition_cols,
+            &props,
+            overwrite,
+        )?;
+        for message in skipped {
+            skip_branch(false, format!("{} in '{}'", message, file.path))?;
+        }
+        written.extend(paths);
+        stems.push(stem);
+        if let Some(progress) = progress {
+            progress.call1((done + 1, total))?;
+        }
+    }
+    Ok(written)
+}
+
+/// Converts `tree_name` in every file matched by `paths` to its own
+/// `<output_dir>/<stem>.parquet`, several files at a time on separate threads
+/// with the GIL released. Returns the paths written, in input order.
+#[pyfunction]
+#[pyo3(signature = (paths, tree_name, output_dir, compression = "zstd", overwrite = false))]
+fn files_to_parquet(
+    py: Python,
+    paths: &Bound<'_, PyAny>,
+    tree_name: &str,
+    output_dir: &str,
+    compression: &str,
+    overwrite: bool,
+) -> PyResult<Vec<String>> {
+    let patterns = match paths.extract::<String>() {
+        Ok(path) => vec![path],
+        Err(_) => paths.extract::<Vec<String>>()?,
+    };
+    let props = WriterProperties::builder()
+        .set_compression(parquet_compression(compression, None)?)
+        .set_max_row_group_size(DEFAULT_ROW_GROUP_SIZE)
+        .build();
+
+    let paths = expand_paths(patterns)?;
+    let mut stems: Vec<String> = Vec::new();
+    for path in &paths {
+        let stem = Path::new(path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if stems.contains(&stem) {
+            return Err(PyValueError::new_err(format!(
+                "Several input files are named '{}'",
+                stem
+            )));
+        }
+        let output_file = Path::new(output_dir).join(format!("{}.parquet", stem));
+        if !overwrite && output_file.exists() {
+            return Err(PyValueError::new_err(format!(
+                "File '{}' exists, use overwrite=True",
+                output_file.display()
+            )));
+        }
+        stems.push(stem);
+    }
+
+    // Each worker opens its own files: RootFile can't be shared across threads.
+    // The GIL is only taken back between waits for results, to check for
+    // Ctrl-C, which stops the workers before their next file. Workers never
+    // take the GIL, which this thread holds while it joins them, so skipped
+    // branches are warned about here once they are done.
+    let next = AtomicUsize::new(0);
+    let cancelled = AtomicBool::new(false);
+    let workers = num_threads().min(paths.len());
+    let mut results = std::thread::scope(|scope| -> PyResult<Vec<_>> {
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..workers {
+            let sender = sender.clone();
+            let (next, cancelled, paths, stems, props) =
+                (&next, &cancelled, &paths, &stems, &props);
+            scope.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(path) = paths.get(i) else {
+                    return;
+                };
+                if cancelled.load(Ordering::Relaxed) {
+                    return;
+                }
+                let file = FileHandle::for_worker(path.clone());
+                let written = file.tree(tree_name).and_then(|tree| {
+                    write_dataset_file(
+                        &tree,
+                        &*file.leaf_lists(tree_name)?,
+                        Path::new(output_dir),
+                        &stems[i],
+                        &[],
+                        props,
+                        overwrite,
+                    )
+                });
+                if sender.send((i, written)).is_err() {
+                    return;
+                }
+            });
+        }
+        drop(sender);
+
+        let receiver = Mutex::new(receiver);
+        let mut results = Vec::new();
+        loop {
+            match recv_interruptible(py, &receiver) {
+                Ok(Some(result)) => results.push(result),
+                Ok(None) => return Ok(results),
+                Err(e) => {
+                    cancelled.store(true, Ordering::Relaxed);
+                    return Err(e);
+                }
+            }
+        }
+    })?;
+    results.sort_by_key(|(i, _)| *i);
+
+    let mut written = Vec::new();
+    for (i, result) in results {
+        match result {
+            Ok((files, skipped)) => {
+                for message in skipped {
+                    skip_branch(false, format!("{} in '{}'", message, paths[i]))?;
+                }
+                written.extend(files);
+            }
+            Err(e) => {
+                return Err(PyValueError::new_err(format!(
+                    "Cannot convert '{}': {}",
+                    paths[i],
+                    e.value(py)
+                )))
+            }
+        }
+    }
+    Ok(written)
+}
+
+/// Percent-encodes a partition value for a Hive-style `column=value`
+/// directory name, as Arrow does, so values holding `/` can't add
+/// directories or leave the output directory.
+fn hive_escape(value: &str) -> String {
+    let mut escaped = String::new();
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                escaped.push(byte as char)
+            }
+            _ => escaped.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    escaped
+}
+
+/// Writes one input tree of a dataset, one row group at a time, opening a
+/// writer per partition directory as its first row shows up. Unless
+/// `overwrite`, existing part files are an error. Returns the files written
+/// and why any branches were skipped, for the caller to warn about: this
+/// runs on worker threads that must not touch Python.
+fn write_dataset_file(
+    tree: &ReaderTree,
+    leaves: &LeafLists,
+    output_dir: &Path,
+    stem: &str,
+    partition_cols: &[String],
+    props: &WriterProperties,
+    overwrite: bool,
+) -> PyResult<(Vec<String>, Vec<String>)> {
+    let mut skipped = Vec::new();
+    let mut iters = branch_column_iters(tree, None, 0, false, false, leaves, &mut |message| {
+        skipped.push(message);
+        Ok(())
+    })?
+    .into_iter()
+    .map(|(_, name, iter)| (name, iter))
+    .collect::<Vec<_>>();
+    let mut partition_idx = Vec::new();
+    for column in partition_cols {
+        match iters.iter().position(|(name, _)| name == column) {
+            Some(i) => partition_idx.push(i),
+            None => {
+                return Err(PyValueError::new_err(format!(
+                    "Partition column '{}' not found",
+                    column
+                )))
+            }
+        }
+    }
+    let data_idx = (0..iters.len())
+        .filter(|i| !partition_idx.contains(i))
+        .collect::<Vec<_>>();
+
+    let mut writers: Vec<(PathBuf, ArrowWriter<File>)> = Vec::new();
+    let mut remaining = tree.entries() as usize;
+    loop {
+        let batch = next_record_batch(&mut iters, remaining.min(DEFAULT_ROW_GROUP_SIZE), true)?;
+        let data = batch
+            .project(&data_idx)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+        let mut groups: Vec<(PathBuf, RecordBatch)> = Vec::new();
+        if partition_idx.is_empty() {
+            groups.push((output_dir.to_path_buf(), data));
+        } else {
+            let mut rows: Vec<(PathBuf, Vec<u32>)> = Vec::new();
+            let mut index: HashMap<PathBuf, usize> = HashMap::new();
+            for row in 0..batch.num_rows() {
+                let mut dir = output_dir.to_path_buf();
+                for (column, &i) in partition_cols.iter().zip(&partition_idx) {
+                    let value = array_value_to_string(batch.column(i), row)
+                        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+                    dir.push(format!("{}={}", column, hive_escape(&value)));
+                }
+                match index.get(&dir) {
+                    Some(&i) => rows[i].1.push(row as u32),
+                    None => {
+                        index.insert(dir.clone(), rows.len());
+                        rows.push((dir, vec![row as u32]));
+                    }
+                }
+            }
+            for (dir, rows) in rows {
+                let part = take_record_batch(&data, &UInt32Array::from(rows))
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?;
+                groups.push((dir, part));
+            }
+        }
+
+        for (dir, part) in groups {
+            let writer = match writers.iter().position(|(d, _)| *d == dir) {
+                Some(i) => &mut writers[i].1,
+                None => {
+                    let output_file = dir.join(format!("{}.parquet", stem));
+                    if !overwrite && output_file.exists() {
+                        return Err(PyValueError::new_err(format!(
+                            "File '{}' exists, use overwrite=True",
+                            output_file.display()
+                        )));
+                    }
+                    std::fs::create_dir_all(&dir)?;
+                    let file = File::create(output_file)?;
+                    let writer = ArrowWriter::try_new(file, part.schema(), Some(props.clone()))
+                        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+                    writers.push((dir, writer));
+                    &mut writers.last_mut().unwrap().1
+                }
+            };
+            writer
+                .write(&part)
+                .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        }
+
+        remaining -= batch.num_rows();
+        if remaining == 0 || batch.num_rows() == 0 {
+            break;
+        }
+    }
+
+    let mut written = Vec::new();
+    for (dir, writer) in writers {
+        writer
+            .close()
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        written.push(
+            dir.join(format!("{}.parquet", stem))
+                .to_string_lossy()
+                .into_owned(),
+        );
+    }
+    Ok((written, skipped))
+}
+
+/// Writes columns to a new ROOT file as one TTree, a branch per column.
+/// `data` is a dict of arrays, as returned by `Tree.arrays()`, or a
+/// `pandas.DataFrame`; columns must be one-dimensional and of equal length.
+#[pyfunction]
+#[pyo3(signature = (data, output_file, tree_name, overwrite = false))]
+fn dataframe_to_root(
+    py: Python,
+    data: &Bound<'_, PyAny>,
+    output_file: &str,
+    tree_name: &str,
+    overwrite: bool,
+) -> PyResult<()> {
+    if !overwrite && Path::new(output_file).exists() {
+        return Err(PyValueError::new_err("File exists, use overwrite=True"));
+    }
+
+    let mut tree = WriterTree::new(tree_name);
+    let mut num_entries = None;
+    for item in data.call_method0("items")?.try_iter()? {
+        let (name, column): (String, Bound<PyAny>) = item?.extract()?;
+        let len = column.len()?;
+        let expected = *num_entries.get_or_insert(len);
+        if len != expected {
+            return Err(PyValueError::new_err(format!(
+                "Column '{}' has {} entries, expected {}",
+                name, len, expected
+            )));
+        }
+        add_branch(py, &mut tree, &name, &column)?;
+    }
+
+    let mut file =
+        RootFile::create(output_file).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    tree.write(&mut file)
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    file.close()
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(())
+}
+
+/// Adds `column` to `tree` as a branch of the type matching its dtype. Plain
+/// lists are taken as bool, int64, float64 or string branches.
+fn add_branch(
+    py: Python,
+    tree: &mut WriterTree,
+    name: &str,
+    column: &Bound<PyAny>,
+) -> PyResult<()> {
+    if column.is_instance_of::<PyList>() {
+        if let Ok(values) = column.extract::<Vec<bool>>() {
+            tree.new_branch(name, values.into_iter());
+        } else if let Ok(values) = column.extract::<Vec<i64>>() {
+            tree.new_branch(name, values.into_iter());
+        } else if let Ok(values) = column.extract::<Vec<f64>>() {
+            tree.new_branch(name, values.into_iter());
+        } else {
+            tree.new_branch(name, column.extract::<Vec<String>>()?.into_iter());
+        }
+        return Ok(());
+    }
+
+    let array = py.import("numpy")?.call_method1("asarray", (column,))?;
+    let dtype: String = array.getattr("dtype")?.getattr("name")?.extract()?;
+    match dtype.as_str() {
+        "float32" => tree.new_branch(name, numpy_values::<f32>(&array)?.into_iter()),
+        "float64" => tree.new_branch(name, numpy_values::<f64>(&array)?.into_iter()),
+        "int8" => tree.new_branch(name, numpy_values::<i8>(&array)?.into_iter()),
+        "int16" => tree.new_branch(name, numpy_values::<i16>(&array)?.into_iter()),
+        "int32" => tree.new_branch(name, numpy_values::<i32>(&array)?.into_iter()),
+        "int64" => tree.new_branch(name, numpy_values::<i64>(&array)?.into_iter()),
+        "uint8" => tree.new_branch(name, numpy_values::<u8>(&array)?.into_iter()),
+        "uint16" => tree.new_branch(name, numpy_values::<u16>(&array)?.into_iter()),
+        "uint32" => tree.new_branch(name, numpy_values::<u32>(&array)?.into_iter()),
+        "uint64" => tree.new_branch(name, numpy_values::<u64>(&array)?.into_iter()),
+        "bool" => tree.new_branch(name, numpy_values::<bool>(&array)?.into_iter()),
+        dtype if dtype == "object" || dtype.starts_with("str") => tree.new_branch(
+            name,
+            array
+                .call_method0("tolist")?
+                .extract::<Vec<String>>()?
+                .into_iter(),
+        ),
+        dtype => {
+            return Err(PyValueError::new_err(format!(
+                "Column '{}' has unsupported dtype '{}'",
+                name, dtype
+            )))
+        }
+    }
+    Ok(())
+}
+
+fn numpy_values<T: Element + Clone>(array: &Bound<PyAny>) -> PyResult<Vec<T>> {
+    Ok(array.extract::<PyReadonlyArray1<T>>()?.as_array().to_vec())
+}
+
+/// Caps the bytes a single `arrays()`, `to_pandas()` or `to_arrow()` call may
+/// read, as estimated by `Tree.size_estimate()`; calls over it raise
+/// `MemoryError` up front. `None` removes the limit.
+#[pyfunction]
+#[pyo3(signature = (limit))]
+fn set_memory_limit(limit: Option<usize>) {
+    MEMORY_LIMIT.store(limit.unwrap_or(0), Ordering::Relaxed);
+}
+
+/// The threads to convert on, as set with `set_num_threads()`.
+fn num_threads() -> usize {
+    match NUM_THREADS.load(Ordering::Relaxed) {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+}
+
+/// Sets how many threads `files_to_parquet()` and `to_parquet()` use; 0, the
+/// default, uses one per CPU core.
+#[pyfunction]
+#[pyo3(signature = (num_threads))]
+fn set_num_threads(num_threads: usize) {
+    NUM_THREADS.store(num_threads, Ordering::Relaxed);
 }
 
+/// The number of threads `files_to_parquet()` and `to_parquet()` use, with 0
+/// from `set_num_threads()` resolved to the CPU core count.
 #[pyfunction]
-fn open(path: String) -> PyResult<PyRootFile> {
-    Ok(PyRootFile::new(path))
+fn get_num_threads() -> usize {
+    num_threads()
 }
 
 #[pyfunction]
@@ -324,11 +4883,26 @@ fn version() -> PyResult<String> {
 /// A Python module to read root files, implemented in Rust.
 #[pymodule]
 fn oxyroot(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("OxyrootWarning", m.py().get_type::<OxyrootWarning>())?;
     m.add_function(wrap_pyfunction!(version, m)?)?;
     m.add_function(wrap_pyfunction!(open, m)?)?;
+    m.add_function(wrap_pyfunction!(open_bytes, m)?)?;
+    m.add_function(wrap_pyfunction!(read, m)?)?;
+    m.add_function(wrap_pyfunction!(set_memory_limit, m)?)?;
+    m.add_function(wrap_pyfunction!(set_num_threads, m)?)?;
+    m.add_function(wrap_pyfunction!(get_num_threads, m)?)?;
+    m.add_function(wrap_pyfunction!(trees_to_parquet_dataset, m)?)?;
+    m.add_function(wrap_pyfunction!(files_to_parquet, m)?)?;
+    m.add_function(wrap_pyfunction!(dataframe_to_root, m)?)?;
     m.add_class::<PyRootFile>()?;
     m.add_class::<PyTree>()?;
     m.add_class::<PyBranch>()?;
+    m.add_class::<PyMultiFile>()?;
+    m.add_class::<PyChain>()?;
     m.add_class::<PyBranchIterator>()?;
+    m.add_class::<PyBatchIterator>()?;
+    m.add_class::<PyArrayIterator>()?;
+    m.add_class::<PyChainBatchIterator>()?;
+    m.add_class::<PyParquetWriter>()?;
     Ok(())
 }